@@ -0,0 +1,361 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::Path,
+    sync::Arc,
+};
+use tokio::{
+    sync::RwLock,
+    task::JoinHandle,
+    time::{Duration, sleep},
+};
+use tracing::{error, info};
+
+use crate::{
+    config::Config,
+    db::DbPool,
+    jellyfin::{JellyfinClient, JellyfinError, SeriesItem},
+};
+
+/// Activity log entry types for playback start/stop all share this prefix.
+const PLAYBACK_EVENT_PREFIX: &str = "VideoPlayback";
+
+#[derive(Debug)]
+pub enum ActivityError {
+    Jellyfin(JellyfinError),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for ActivityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActivityError::Jellyfin(err) => write!(f, "Jellyfin error: {err}"),
+            ActivityError::Database(err) => write!(f, "Database error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ActivityError {}
+
+impl From<JellyfinError> for ActivityError {
+    fn from(value: JellyfinError) -> Self {
+        ActivityError::Jellyfin(value)
+    }
+}
+
+impl From<sqlx::Error> for ActivityError {
+    fn from(value: sqlx::Error) -> Self {
+        ActivityError::Database(value)
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ActivitySyncSummary {
+    pub users: usize,
+    pub series: usize,
+    pub matched_shows: usize,
+    pub unmatched_series: usize,
+}
+
+/// Aggregated playback for one show, as stored in `show_activity`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ShowActivity {
+    pub last_played_at: Option<i64>,
+    pub play_count: i64,
+    pub active_viewers: i64,
+}
+
+/// One user's playback of a series, from episode `UserData` or the activity log.
+#[derive(Debug, Clone)]
+pub struct PlayRecord {
+    pub series_id: String,
+    pub user_id: String,
+    pub played_at: Option<i64>,
+    pub play_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct LibraryShow {
+    pub id: i64,
+    pub path: String,
+    pub title: Option<String>,
+}
+
+/// Start the background task that mirrors Jellyfin playback into `show_activity`.
+pub fn start_sync(db: DbPool, config: Arc<RwLock<Config>>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Starting Jellyfin activity sync background task");
+
+        loop {
+            let config_snapshot = config.read().await.clone();
+            match sync_once(&db, &config_snapshot).await {
+                Ok(summary) => info!(
+                    users = summary.users,
+                    matched_shows = summary.matched_shows,
+                    unmatched_series = summary.unmatched_series,
+                    "Jellyfin activity sync finished"
+                ),
+                Err(ActivityError::Jellyfin(JellyfinError::NotConfigured)) => {}
+                Err(err) => error!(%err, "Jellyfin activity sync failed"),
+            }
+
+            let interval_minutes = config_snapshot.activity.sync_interval_minutes.max(1);
+            sleep(Duration::from_secs(u64::from(interval_minutes) * 60)).await;
+        }
+    })
+}
+
+/// Pulls every user's episode play data plus recent playback events and
+/// replaces the contents of `show_activity` with the per-show totals.
+pub async fn sync_once(db: &DbPool, config: &Config) -> Result<ActivitySyncSummary, ActivityError> {
+    let client = JellyfinClient::from_config(config)?;
+    let now = Utc::now();
+    let window_start = now - ChronoDuration::days(i64::from(config.activity.active_viewer_days));
+
+    let series = client.list_series().await?;
+    let users = client.list_users().await?;
+
+    let mut plays = Vec::new();
+    let mut episode_series = HashMap::new();
+    for user in &users {
+        for episode in client.list_user_episodes(&user.id).await? {
+            let Some(series_id) = episode.series_id else {
+                continue;
+            };
+            episode_series.insert(episode.id, series_id.clone());
+
+            let Some(data) = episode.user_data else {
+                continue;
+            };
+            let played_at = data.last_played_date.as_deref().and_then(parse_timestamp);
+            if data.play_count > 0 || played_at.is_some() {
+                plays.push(PlayRecord {
+                    series_id,
+                    user_id: user.id.clone(),
+                    played_at,
+                    play_count: data.play_count,
+                });
+            }
+        }
+    }
+
+    // The activity log also covers plays that never reached the "played" mark.
+    for entry in client.activity_log(window_start).await? {
+        if !entry
+            .kind
+            .as_deref()
+            .is_some_and(|kind| kind.starts_with(PLAYBACK_EVENT_PREFIX))
+        {
+            continue;
+        }
+        let (Some(item_id), Some(user_id)) = (entry.item_id, entry.user_id) else {
+            continue;
+        };
+        let Some(series_id) = episode_series.get(&item_id) else {
+            continue;
+        };
+        plays.push(PlayRecord {
+            series_id: series_id.clone(),
+            user_id,
+            played_at: parse_timestamp(&entry.date),
+            play_count: 0,
+        });
+    }
+
+    let shows = sqlx::query_as::<_, LibraryShow>("SELECT id, path, title FROM shows")
+        .fetch_all(db)
+        .await?;
+    let series_shows = match_series(&series, &shows);
+    let activity = aggregate(&plays, &series_shows, window_start.timestamp());
+    store_activity(db, &activity, now.timestamp()).await?;
+
+    Ok(ActivitySyncSummary {
+        users: users.len(),
+        series: series.len(),
+        matched_shows: activity.len(),
+        unmatched_series: series.len() - series_shows.len(),
+    })
+}
+
+/// Maps Jellyfin series ids to show ids.
+///
+/// Jellyfin often sees the media under different mount points than JellyMover,
+/// so an exact path match falls back to the folder name and then the title.
+pub fn match_series(series: &[SeriesItem], shows: &[LibraryShow]) -> HashMap<String, i64> {
+    let by_path: HashMap<&str, i64> = shows
+        .iter()
+        .map(|show| (show.path.as_str(), show.id))
+        .collect();
+    let by_folder: HashMap<String, i64> = shows
+        .iter()
+        .filter_map(|show| Some((folder_key(&show.path)?, show.id)))
+        .collect();
+    let by_title: HashMap<String, i64> = shows
+        .iter()
+        .filter_map(|show| Some((show.title.as_deref()?.trim().to_lowercase(), show.id)))
+        .collect();
+
+    series
+        .iter()
+        .filter_map(|item| {
+            let path = item.path.as_deref();
+            let show_id = path
+                .and_then(|path| by_path.get(path.trim_end_matches('/')).copied())
+                .or_else(|| {
+                    path.and_then(folder_key)
+                        .and_then(|key| by_folder.get(&key).copied())
+                })
+                .or_else(|| {
+                    let name = item.name.as_deref()?.trim().to_lowercase();
+                    by_title.get(&name).copied()
+                })?;
+            Some((item.id.clone(), show_id))
+        })
+        .collect()
+}
+
+/// Folds plays into per-show totals. A user is an active viewer of a show when
+/// any of their plays happened at or after `window_start`.
+pub fn aggregate(
+    plays: &[PlayRecord],
+    series_shows: &HashMap<String, i64>,
+    window_start: i64,
+) -> HashMap<i64, ShowActivity> {
+    let mut totals: HashMap<i64, (ShowActivity, HashSet<&str>)> = HashMap::new();
+
+    for play in plays {
+        let Some(show_id) = series_shows.get(&play.series_id) else {
+            continue;
+        };
+        let (activity, viewers) = totals.entry(*show_id).or_default();
+        activity.play_count += play.play_count.max(0);
+        activity.last_played_at = activity.last_played_at.max(play.played_at);
+        if play.played_at.is_some_and(|at| at >= window_start) {
+            viewers.insert(play.user_id.as_str());
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|(show_id, (mut activity, viewers))| {
+            activity.active_viewers = viewers.len() as i64;
+            (show_id, activity)
+        })
+        .collect()
+}
+
+async fn store_activity(
+    db: &DbPool,
+    activity: &HashMap<i64, ShowActivity>,
+    synced_at: i64,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+    sqlx::query("DELETE FROM show_activity")
+        .execute(&mut *tx)
+        .await?;
+
+    for (show_id, show_activity) in activity {
+        sqlx::query(
+            r#"
+            INSERT INTO show_activity (show_id, last_played_at, play_count, active_viewers, synced_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(show_id)
+        .bind(show_activity.last_played_at)
+        .bind(show_activity.play_count)
+        .bind(show_activity.active_viewers)
+        .bind(synced_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+fn folder_key(path: &str) -> Option<String> {
+    Path::new(path.trim_end_matches('/'))
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+}
+
+fn parse_timestamp(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|date| date.timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(id: &str, name: &str, path: &str) -> SeriesItem {
+        SeriesItem {
+            id: id.to_string(),
+            name: Some(name.to_string()),
+            path: Some(path.to_string()),
+        }
+    }
+
+    fn play(series_id: &str, user_id: &str, played_at: i64, play_count: i64) -> PlayRecord {
+        PlayRecord {
+            series_id: series_id.to_string(),
+            user_id: user_id.to_string(),
+            played_at: Some(played_at),
+            play_count,
+        }
+    }
+
+    #[test]
+    fn matches_series_across_mounts_and_counts_recent_viewers() {
+        let shows = vec![
+            LibraryShow {
+                id: 1,
+                path: "/media/hot/Frieren".to_string(),
+                title: Some("Frieren".to_string()),
+            },
+            LibraryShow {
+                id: 2,
+                path: "/media/cold/Mushishi (2005)".to_string(),
+                title: Some("Mushishi".to_string()),
+            },
+        ];
+        let series_items = vec![
+            series("a", "Frieren: Beyond Journey's End", "/data/tv/Frieren/"),
+            series("b", "Mushishi", "/data/anime/Mushi-shi"),
+            series("c", "Unknown", "/data/tv/Unknown"),
+        ];
+
+        let series_shows = match_series(&series_items, &shows);
+        assert_eq!(series_shows.get("a"), Some(&1));
+        assert_eq!(series_shows.get("b"), Some(&2));
+        assert!(!series_shows.contains_key("c"));
+
+        let plays = vec![
+            play("a", "alice", 100, 3),
+            play("a", "bob", 500, 1),
+            play("a", "alice", 900, 0),
+            play("b", "alice", 50, 2),
+        ];
+        let activity = aggregate(&plays, &series_shows, 400);
+
+        assert_eq!(
+            activity.get(&1),
+            Some(&ShowActivity {
+                last_played_at: Some(900),
+                play_count: 4,
+                active_viewers: 2,
+            })
+        );
+        assert_eq!(
+            activity.get(&2),
+            Some(&ShowActivity {
+                last_played_at: Some(50),
+                play_count: 2,
+                active_viewers: 0,
+            })
+        );
+    }
+}
@@ -1,46 +1,526 @@
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashSet,
     fmt, fs, io,
     path::{Path, PathBuf},
 };
 use url::Url;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
-    pub hot_root: String,
-    pub cold_root: String,
+    /// Storage tiers, fastest first. A show's `location` is the name of the
+    /// tier whose root contains it.
+    pub tiers: Vec<TierConfig>,
+    /// Pre-tier configs had a fixed pair of roots; `migrate_legacy_roots`
+    /// folds them into `tiers`.
+    #[serde(rename = "hot_root", skip_serializing)]
+    pub legacy_hot_root: Option<String>,
+    #[serde(rename = "cold_root", skip_serializing)]
+    pub legacy_cold_root: Option<String>,
     pub library_paths: Vec<String>,
+    /// Caps on how much of a tier each library may occupy.
+    #[serde(alias = "hot_quotas")]
+    pub library_quotas: Vec<LibraryQuota>,
+    /// Libraries holding a single kind of media; folders in other libraries
+    /// are classified one by one.
+    pub library_kinds: Vec<LibraryKind>,
     pub jellyfin: JellyfinConfig,
+    pub job_retention: JobRetentionConfig,
+    pub policies: PolicyConfig,
+    pub eviction: EvictionConfig,
+    pub activity: ActivityConfig,
+    pub playback_guard: PlaybackGuardConfig,
+    pub prefetch: PrefetchConfig,
+    pub stable_paths: StablePathsConfig,
+    pub mergerfs: MergerfsConfig,
+    pub watcher: WatcherConfig,
+    pub scan: ScanConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Upper bound for `scan.parallelism` and per-tier `scan_parallelism`.
+const MAX_SCAN_PARALLELISM: u32 = 64;
+
+/// Tier names used when migrating a legacy `hot_root`/`cold_root` config.
+pub const LEGACY_HOT_TIER: &str = "hot";
+pub const LEGACY_COLD_TIER: &str = "cold";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TierConfig {
+    /// Unique; stored as `shows.location` and used as a move target.
+    pub name: String,
+    /// Directories making up the tier; each show lives under one of them.
+    pub roots: Vec<String>,
+    /// Single-root form written by earlier versions; folded into `roots` by
+    /// `migrate_legacy_roots`.
+    #[serde(rename = "root", skip_serializing)]
+    pub legacy_root: Option<String>,
+    pub class: StorageClass,
+    pub limits: TierLimits,
+    /// How a show entering the tier is assigned one of `roots`.
+    pub placement: PlacementStrategy,
+    /// Show folders scanned at once on this tier; defaults by `class`.
+    pub scan_parallelism: Option<u32>,
+}
+
+impl TierConfig {
+    /// How many show folders a scan reads at once on this tier. Seeks are cheap
+    /// on flash and expensive on spinning disks.
+    pub fn scan_parallelism(&self) -> usize {
+        let parallelism = self.scan_parallelism.unwrap_or(match self.class {
+            StorageClass::Nvme => 8,
+            StorageClass::Ssd => 4,
+            StorageClass::Hdd => 2,
+            StorageClass::Archive => 1,
+        });
+        parallelism.max(1) as usize
+    }
+
+    /// The configured roots, skipping blank entries.
+    pub fn root_paths(&self) -> Vec<PathBuf> {
+        self.roots
+            .iter()
+            .map(|root| root.trim())
+            .filter(|root| !root.is_empty())
+            .map(PathBuf::from)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlacementStrategy {
+    /// The root with the most free space once queued moves land.
+    #[default]
+    MostFree,
+    /// Each move takes the root after the one the previous move into the tier used.
+    RoundRobin,
+    /// The root already holding the show's library folder, falling back to `MostFree`.
+    StickyPerLibrary,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageClass {
+    Nvme,
+    Ssd,
+    #[default]
+    Hdd,
+    Archive,
+}
+
+/// Moves into a tier are refused when they would break one of these.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TierLimits {
+    /// Highest projected usage, as a percentage of the tier's capacity.
+    pub max_used_percent: Option<f64>,
+    /// Free space that must remain after the move.
+    pub min_free_bytes: Option<u64>,
+}
+
+/// A quota for one `library_paths` entry within a tier. The library is
+/// identified by its path below its tier root, so the same folder in any
+/// tier counts toward it. Exactly one of the limits must be set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LibraryQuota {
+    pub library: String,
+    /// Tier the quota applies to; defaults to the fastest tier.
+    pub tier: Option<String>,
+    pub max_bytes: Option<u64>,
+    /// Share of the tier's total capacity.
+    pub max_percent: Option<f64>,
+}
+
+/// What a folder directly inside a library holds. Stored as `shows.kind`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+    #[default]
+    Series,
+    Movie,
+    /// Neither, e.g. a folder without videos.
+    Other,
+}
+
+impl MediaKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MediaKind::Series => "series",
+            MediaKind::Movie => "movie",
+            MediaKind::Other => "other",
+        }
+    }
+}
+
+/// Forces every folder of one `library_paths` entry to `kind`. Like quotas,
+/// the library is identified by its path below its tier root.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LibraryKind {
+    pub library: String,
+    pub kind: MediaKind,
+}
+
+impl Config {
+    /// Folds the pre-tier `hot_root`/`cold_root` fields into tiers named
+    /// `hot` and `cold`, matching the `location` values already stored on
+    /// shows, and single-root tiers into `roots`. Returns whether anything
+    /// changed.
+    pub fn migrate_legacy_roots(&mut self) -> bool {
+        let hot = self.legacy_hot_root.take();
+        let cold = self.legacy_cold_root.take();
+        let mut changed = false;
+
+        for tier in &mut self.tiers {
+            if let Some(root) = tier.legacy_root.take() {
+                changed = true;
+                if !root.trim().is_empty() && !tier.roots.contains(&root) {
+                    tier.roots.insert(0, root);
+                }
+            }
+        }
+
+        for (name, root, class) in [
+            (LEGACY_HOT_TIER, hot, StorageClass::Ssd),
+            (LEGACY_COLD_TIER, cold, StorageClass::Hdd),
+        ] {
+            let Some(root) = root.filter(|root| !root.trim().is_empty()) else {
+                continue;
+            };
+            changed = true;
+            if let Some(tier) = self.tiers.iter_mut().find(|tier| tier.name == name) {
+                tier.roots = vec![root];
+                continue;
+            }
+
+            let tier = TierConfig {
+                name: name.to_string(),
+                roots: vec![root],
+                class,
+                ..TierConfig::default()
+            };
+            if name == LEGACY_HOT_TIER {
+                self.tiers.insert(0, tier);
+            } else {
+                self.tiers.push(tier);
+            }
+        }
+
+        changed
+    }
+
+    pub fn tier(&self, name: &str) -> Option<&TierConfig> {
+        self.tiers.iter().find(|tier| tier.name == name.trim())
+    }
+
+    /// The tier with a root containing `path`.
+    pub fn tier_for_path(&self, path: &Path) -> Option<&TierConfig> {
+        self.locate(path).map(|(tier, _)| tier)
+    }
+
+    /// The tier and the specific root of it that contain `path`.
+    pub fn locate(&self, path: &Path) -> Option<(&TierConfig, PathBuf)> {
+        self.tiers
+            .iter()
+            .flat_map(|tier| tier.root_paths().into_iter().map(move |root| (tier, root)))
+            .filter(|(_, root)| path.starts_with(root))
+            .max_by_key(|(_, root)| root.components().count())
+    }
+
+    /// Every root of every tier.
+    pub fn all_roots(&self) -> Vec<PathBuf> {
+        self.tiers.iter().flat_map(TierConfig::root_paths).collect()
+    }
+
+    pub fn fastest_tier(&self) -> Option<&TierConfig> {
+        self.tiers.first()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct JellyfinConfig {
     pub url: String,
     pub api_key: String,
 }
 
-impl Default for Config {
+/// How long finished jobs are kept before the background pruner deletes them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobRetentionConfig {
+    /// Days to keep successful jobs; `None` keeps them forever.
+    pub success_days: Option<u32>,
+    /// Days to keep failed jobs; `None` keeps them forever.
+    pub failed_days: Option<u32>,
+    /// Directory that receives gzip-compressed JSON-lines archives of pruned
+    /// jobs. Empty disables archiving.
+    pub archive_dir: String,
+}
+
+/// Automatic tiering: rules evaluated periodically over the `shows` table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolicyConfig {
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    /// Upper bound on jobs queued by a single evaluation pass.
+    pub max_jobs_per_run: u32,
+    /// Evaluated in order; the first matching rule decides a show's target.
+    pub rules: Vec<PolicyRule>,
+}
+
+impl Default for PolicyConfig {
     fn default() -> Self {
         Self {
-            hot_root: String::new(),
-            cold_root: String::new(),
-            library_paths: Vec::new(),
-            jellyfin: JellyfinConfig::default(),
+            enabled: false,
+            interval_minutes: 60,
+            max_jobs_per_run: 10,
+            rules: Vec::new(),
         }
     }
 }
 
-impl Default for JellyfinConfig {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Name of the tier the show should be moved to when every condition matches.
+    pub target: String,
+    pub conditions: Vec<PolicyCondition>,
+}
+
+/// A single predicate over a show. Conditions whose input is unknown for a
+/// show (e.g. no rating) do not match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PolicyCondition {
+    /// Not played for at least `days`; never-played shows count from when they were first seen.
+    NotPlayedForDays {
+        days: u32,
+    },
+    /// A new episode appeared within the last `days`.
+    EpisodeAddedWithinDays {
+        days: u32,
+    },
+    /// The show itself was first seen within the last `days`.
+    AddedWithinDays {
+        days: u32,
+    },
+    SizeAboveBytes {
+        bytes: u64,
+    },
+    SizeBelowBytes {
+        bytes: u64,
+    },
+    RatingBelow {
+        rating: f64,
+    },
+    RatingAbove {
+        rating: f64,
+    },
+}
+
+/// Keeps the fastest tier between two usage watermarks by moving shows to the next tier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvictionConfig {
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    /// Eviction starts once projected usage of the fastest tier exceeds this percentage.
+    pub high_watermark_percent: f64,
+    /// Eviction queues moves until projected usage falls to this percentage.
+    pub low_watermark_percent: f64,
+    /// Score added per day since the show was last played or received new content.
+    pub idle_weight: f64,
+    /// Score added per GiB of show size, so large shows free space first.
+    pub size_weight: f64,
+}
+
+impl Default for EvictionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 15,
+            high_watermark_percent: 85.0,
+            low_watermark_percent: 70.0,
+            idle_weight: 1.0,
+            size_weight: 0.1,
+        }
+    }
+}
+
+/// How Jellyfin playback history is pulled into `show_activity`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivityConfig {
+    /// Minutes between syncs; runs only while Jellyfin is configured.
+    pub sync_interval_minutes: u32,
+    /// A user counts as an active viewer of a show if they played it within this many days.
+    pub active_viewer_days: u32,
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        Self {
+            sync_interval_minutes: 30,
+            active_viewer_days: 30,
+        }
+    }
+}
+
+/// Copies the next episodes of shows people are watching from slower tiers into the fastest one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrefetchConfig {
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    /// Episodes to keep hot from each user's "Next Up" episode onwards.
+    pub episodes_ahead: u32,
+    /// Upper bound on bytes of prefetched episodes held in the fastest tier.
+    pub hot_budget_bytes: u64,
+}
+
+impl Default for PrefetchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 30,
+            episodes_ahead: 3,
+            hot_budget_bytes: 50 * 1024 * 1024 * 1024,
+        }
+    }
+}
+
+/// Gives every show a fixed path in a directory of symlinks that Jellyfin
+/// points at, so moves between tiers do not look like deletions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StablePathsConfig {
+    pub enabled: bool,
+    /// Directory holding the per-show symlinks, mirroring the layout below the tier roots.
+    pub farm_root: String,
+}
+
+impl StablePathsConfig {
+    pub fn farm_root_path(&self) -> Option<PathBuf> {
+        let trimmed = self.farm_root.trim();
+        (self.enabled && !trimmed.is_empty()).then(|| PathBuf::from(trimmed))
+    }
+}
+
+/// Watches the library folders and rescans shows as their files change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatcherConfig {
+    pub enabled: bool,
+    /// Quiet period after the last change in a show folder before it is rescanned.
+    pub debounce_seconds: u32,
+}
+
+impl Default for WatcherConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            debounce_seconds: 10,
+        }
+    }
+}
+
+/// How filesystem scans share out their work.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanConfig {
+    /// Show folders scanned at once in libraries outside every tier.
+    pub parallelism: u32,
+    /// Shows written to the database per transaction.
+    pub batch_size: u32,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            parallelism: 2,
+            batch_size: 200,
+        }
+    }
+}
+
+/// A mergerfs union whose branches are the tier roots. Jellyfin reads the
+/// union, so moving a show between branches does not change its path there.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MergerfsConfig {
+    pub enabled: bool,
+    /// Mount point of the union.
+    pub union_root: String,
+    /// Branch directories in union order. When empty they are read from the
+    /// running mount by `mergerfs::detect_branches`.
+    pub branches: Vec<String>,
+    #[serde(skip)]
+    pub detected_branches: Vec<PathBuf>,
+}
+
+impl MergerfsConfig {
+    pub fn union_root_path(&self) -> Option<PathBuf> {
+        let trimmed = self.union_root.trim();
+        (self.enabled && !trimmed.is_empty()).then(|| PathBuf::from(trimmed))
+    }
+
+    /// Configured branches, falling back to the ones read from the mount.
+    pub fn branch_paths(&self) -> Vec<PathBuf> {
+        let configured: Vec<PathBuf> = self
+            .branches
+            .iter()
+            .map(|branch| branch.trim())
+            .filter(|branch| !branch.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        if configured.is_empty() {
+            self.detected_branches.clone()
+        } else {
+            configured
+        }
+    }
+}
+
+/// What the job worker does when Jellyfin reports a show is being streamed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybackGuardMode {
+    /// Park the job as `waiting_for_playback` and re-check later.
+    #[default]
+    Defer,
+    /// Fail the job without moving anything.
+    Skip,
+    /// Move regardless of playback.
+    Ignore,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlaybackGuardConfig {
+    pub mode: PlaybackGuardMode,
+    /// Seconds between playback re-checks for deferred jobs.
+    pub recheck_seconds: u32,
+}
+
+impl Default for PlaybackGuardConfig {
     fn default() -> Self {
         Self {
-            url: String::new(),
-            api_key: String::new(),
+            mode: PlaybackGuardMode::Defer,
+            recheck_seconds: 60,
         }
     }
 }
 
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfigStore {
     path: PathBuf,
@@ -143,23 +623,56 @@ impl fmt::Display for ConfigValidationError {
 impl std::error::Error for ConfigValidationError {}
 
 pub fn validate_config(config: &Config) -> Result<(), ConfigValidationError> {
-    let hot_root = validate_root_path("hot_root", &config.hot_root)?;
-    let cold_root = validate_root_path("cold_root", &config.cold_root)?;
-
+    validate_tiers(&config.tiers)?;
     validate_library_paths(&config.library_paths)?;
+    validate_library_quotas(config)?;
+    validate_library_kinds(config)?;
+    validate_job_retention(&config.job_retention)?;
+    validate_policies(&config.policies, &config.tiers)?;
+    validate_eviction(&config.eviction)?;
+    validate_stable_paths(config)?;
+    validate_mergerfs(config)?;
 
-    if let (Some(hot), Some(cold)) = (hot_root.as_ref(), cold_root.as_ref()) {
-        if hot == cold {
-            return Err(ConfigValidationError::new(
-                "hot_root and cold_root cannot be the same directory",
-            ));
-        }
+    if config.activity.sync_interval_minutes == 0 || config.activity.active_viewer_days == 0 {
+        return Err(ConfigValidationError::new(
+            "activity sync_interval_minutes and active_viewer_days must be at least 1",
+        ));
+    }
 
-        if hot.starts_with(cold) || cold.starts_with(hot) {
-            return Err(ConfigValidationError::new(
-                "hot_root and cold_root cannot be nested inside each other",
-            ));
-        }
+    if config.prefetch.interval_minutes == 0 || config.prefetch.episodes_ahead == 0 {
+        return Err(ConfigValidationError::new(
+            "prefetch interval_minutes and episodes_ahead must be at least 1",
+        ));
+    }
+
+    if !(1..=MAX_SCAN_PARALLELISM).contains(&config.scan.parallelism)
+        || config
+            .tiers
+            .iter()
+            .filter_map(|tier| tier.scan_parallelism)
+            .any(|parallelism| !(1..=MAX_SCAN_PARALLELISM).contains(&parallelism))
+    {
+        return Err(ConfigValidationError::new(format!(
+            "scan parallelism must be between 1 and {MAX_SCAN_PARALLELISM}"
+        )));
+    }
+
+    if config.scan.batch_size == 0 {
+        return Err(ConfigValidationError::new(
+            "scan batch_size must be at least 1",
+        ));
+    }
+
+    if config.watcher.debounce_seconds == 0 {
+        return Err(ConfigValidationError::new(
+            "watcher debounce_seconds must be at least 1",
+        ));
+    }
+
+    if config.playback_guard.recheck_seconds == 0 {
+        return Err(ConfigValidationError::new(
+            "playback_guard recheck_seconds must be at least 1",
+        ));
     }
 
     let jellyfin_url = config.jellyfin.url.trim();
@@ -172,11 +685,13 @@ pub fn validate_config(config: &Config) -> Result<(), ConfigValidationError> {
     Ok(())
 }
 
+/// Moves need at least two tiers, each with a root.
 pub fn config_is_ready(config: &Config) -> bool {
-    let hot_ready = !config.hot_root.trim().is_empty();
-    let cold_ready = !config.cold_root.trim().is_empty();
-
-    hot_ready && cold_ready
+    config.tiers.len() >= 2
+        && config
+            .tiers
+            .iter()
+            .all(|tier| !tier.root_paths().is_empty())
 }
 
 fn validate_root_path(field: &str, value: &str) -> Result<Option<PathBuf>, ConfigValidationError> {
@@ -211,6 +726,319 @@ fn validate_library_paths(paths: &[String]) -> Result<(), ConfigValidationError>
     Ok(())
 }
 
+fn validate_tiers(tiers: &[TierConfig]) -> Result<(), ConfigValidationError> {
+    let mut names: Vec<&str> = Vec::new();
+    let mut roots: Vec<(&str, PathBuf)> = Vec::new();
+
+    for tier in tiers {
+        let name = tier.name.as_str();
+        // Names are stored on rows and used as JSON keys in SQL, so keep them plain.
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(ConfigValidationError::new(format!(
+                "tier name '{name}' must be non-empty and use only letters, digits, '-' and '_'"
+            )));
+        }
+        if names.contains(&name) {
+            return Err(ConfigValidationError::new(format!(
+                "tier name '{name}' is used more than once"
+            )));
+        }
+        names.push(name);
+
+        if let Some(percent) = tier.limits.max_used_percent
+            && !(percent > 0.0 && percent <= 100.0)
+        {
+            return Err(ConfigValidationError::new(format!(
+                "tier '{name}' max_used_percent must be between 0 and 100"
+            )));
+        }
+
+        for value in &tier.roots {
+            let Some(root) = validate_root_path(&format!("tier '{name}' root"), value)? else {
+                continue;
+            };
+            for (other, other_root) in &roots {
+                if *other_root == root {
+                    return Err(ConfigValidationError::new(format!(
+                        "tier '{name}' root '{}' is already a root of tier '{other}'",
+                        root.display()
+                    )));
+                }
+                if root.starts_with(other_root) || other_root.starts_with(&root) {
+                    return Err(ConfigValidationError::new(format!(
+                        "tier roots '{}' ({other}) and '{}' ({name}) cannot be nested inside each other",
+                        other_root.display(),
+                        root.display()
+                    )));
+                }
+            }
+            roots.push((name, root));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_library_quotas(config: &Config) -> Result<(), ConfigValidationError> {
+    let mut seen = Vec::new();
+
+    for quota in &config.library_quotas {
+        let library = quota.library.trim();
+        if !config
+            .library_paths
+            .iter()
+            .any(|path| path.trim() == library)
+        {
+            return Err(ConfigValidationError::new(format!(
+                "library_quotas library '{library}' must be one of library_paths"
+            )));
+        }
+
+        let tier = match quota.tier.as_deref() {
+            Some(name) => config.tier(name).ok_or_else(|| {
+                ConfigValidationError::new(format!(
+                    "library_quotas tier '{name}' for '{library}' is not a configured tier"
+                ))
+            })?,
+            None => config.fastest_tier().ok_or_else(|| {
+                ConfigValidationError::new("library_quotas need at least one tier")
+            })?,
+        };
+
+        let path = Path::new(library);
+        let Some(key) = config
+            .locate(path)
+            .and_then(|(_, root)| path.strip_prefix(root).ok().map(Path::to_path_buf))
+        else {
+            return Err(ConfigValidationError::new(format!(
+                "library_quotas library '{library}' must be inside a tier root"
+            )));
+        };
+        if seen.contains(&(tier.name.as_str(), key.clone())) {
+            return Err(ConfigValidationError::new(format!(
+                "library_quotas has more than one quota for library '{library}' in tier '{}'",
+                tier.name
+            )));
+        }
+        seen.push((tier.name.as_str(), key));
+
+        match (quota.max_bytes, quota.max_percent) {
+            (Some(_), None) => {}
+            (None, Some(percent)) if percent > 0.0 && percent <= 100.0 => {}
+            (None, Some(_)) => {
+                return Err(ConfigValidationError::new(format!(
+                    "library_quotas max_percent for '{library}' must be between 0 and 100"
+                )));
+            }
+            _ => {
+                return Err(ConfigValidationError::new(format!(
+                    "library_quotas entry for '{library}' needs exactly one of max_bytes or max_percent"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_library_kinds(config: &Config) -> Result<(), ConfigValidationError> {
+    let mut seen = HashSet::new();
+    for entry in &config.library_kinds {
+        let library = entry.library.trim();
+        if !config
+            .library_paths
+            .iter()
+            .any(|path| path.trim() == library)
+        {
+            return Err(ConfigValidationError::new(format!(
+                "library_kinds library '{library}' must be one of library_paths"
+            )));
+        }
+        if !seen.insert(library) {
+            return Err(ConfigValidationError::new(format!(
+                "library_kinds has more than one entry for library '{library}'"
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn validate_job_retention(retention: &JobRetentionConfig) -> Result<(), ConfigValidationError> {
+    if retention.success_days == Some(0) || retention.failed_days == Some(0) {
+        return Err(ConfigValidationError::new(
+            "job_retention days must be at least 1; leave unset to keep jobs forever",
+        ));
+    }
+
+    let archive_dir = retention.archive_dir.trim();
+    if !archive_dir.is_empty() && !Path::new(archive_dir).is_absolute() {
+        return Err(ConfigValidationError::new(format!(
+            "job_retention archive_dir '{archive_dir}' must be an absolute path"
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn validate_policies(
+    policies: &PolicyConfig,
+    tiers: &[TierConfig],
+) -> Result<(), ConfigValidationError> {
+    if policies.interval_minutes == 0 {
+        return Err(ConfigValidationError::new(
+            "policies interval_minutes must be at least 1",
+        ));
+    }
+
+    for rule in &policies.rules {
+        if rule.name.trim().is_empty() {
+            return Err(ConfigValidationError::new("policy rules must have a name"));
+        }
+        if !tiers.iter().any(|tier| tier.name == rule.target.trim()) {
+            return Err(ConfigValidationError::new(format!(
+                "policy rule '{}' target '{}' is not a configured tier",
+                rule.name, rule.target
+            )));
+        }
+        if rule.conditions.is_empty() {
+            return Err(ConfigValidationError::new(format!(
+                "policy rule '{}' needs at least one condition",
+                rule.name
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_eviction(eviction: &EvictionConfig) -> Result<(), ConfigValidationError> {
+    if eviction.interval_minutes == 0 {
+        return Err(ConfigValidationError::new(
+            "eviction interval_minutes must be at least 1",
+        ));
+    }
+
+    let low = eviction.low_watermark_percent;
+    let high = eviction.high_watermark_percent;
+    if !(0.0..=100.0).contains(&low) || !(0.0..=100.0).contains(&high) || low >= high {
+        return Err(ConfigValidationError::new(
+            "eviction watermarks must satisfy 0 <= low_watermark_percent < high_watermark_percent <= 100",
+        ));
+    }
+
+    if eviction.idle_weight < 0.0 || eviction.size_weight < 0.0 {
+        return Err(ConfigValidationError::new(
+            "eviction weights cannot be negative",
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_stable_paths(config: &Config) -> Result<(), ConfigValidationError> {
+    if !config.stable_paths.enabled {
+        return Ok(());
+    }
+
+    let farm_root = config.stable_paths.farm_root.trim();
+    if !Path::new(farm_root).is_absolute() {
+        return Err(ConfigValidationError::new(
+            "stable_paths farm_root must be an absolute path when stable paths are enabled",
+        ));
+    }
+    let farm_root = Path::new(farm_root);
+    ensure_directory(farm_root, "stable_paths farm_root")?;
+
+    if let Some(root) = config
+        .all_roots()
+        .into_iter()
+        .find(|root| root.starts_with(farm_root) || farm_root.starts_with(root))
+    {
+        return Err(ConfigValidationError::new(format!(
+            "stable_paths farm_root '{}' and tier root '{}' cannot be nested inside each other",
+            farm_root.display(),
+            root.display()
+        )));
+    }
+    if let Some(library) = config
+        .library_paths
+        .iter()
+        .find(|library| Path::new(library.trim()).starts_with(farm_root))
+    {
+        return Err(ConfigValidationError::new(format!(
+            "library path '{}' is inside the stable_paths farm_root; list the tier folders instead",
+            library.trim()
+        )));
+    }
+    // Prefetched episodes leave the show folder the farm link points at.
+    if config.prefetch.enabled {
+        return Err(ConfigValidationError::new(
+            "prefetch cannot be enabled together with stable_paths",
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_mergerfs(config: &Config) -> Result<(), ConfigValidationError> {
+    if !config.mergerfs.enabled {
+        return Ok(());
+    }
+    if config.stable_paths.enabled {
+        return Err(ConfigValidationError::new(
+            "mergerfs and stable_paths cannot both be enabled",
+        ));
+    }
+
+    let union_root = config.mergerfs.union_root.trim();
+    if !Path::new(union_root).is_absolute() {
+        return Err(ConfigValidationError::new(
+            "mergerfs union_root must be an absolute path when mergerfs mode is enabled",
+        ));
+    }
+    let union_root = Path::new(union_root);
+    ensure_directory(union_root, "mergerfs union_root")?;
+
+    let branches = config.mergerfs.branch_paths();
+    if branches.is_empty() {
+        return Err(ConfigValidationError::new(format!(
+            "could not read the branches of the mergerfs union at '{}'; list them in mergerfs branches",
+            union_root.display()
+        )));
+    }
+    for branch in &branches {
+        if !branch.is_absolute() {
+            return Err(ConfigValidationError::new(format!(
+                "mergerfs branch '{}' must be an absolute path",
+                branch.display()
+            )));
+        }
+        if branch.starts_with(union_root) || union_root.starts_with(branch) {
+            return Err(ConfigValidationError::new(format!(
+                "mergerfs branch '{}' and union_root '{}' cannot be nested inside each other",
+                branch.display(),
+                union_root.display()
+            )));
+        }
+    }
+    if let Some(root) = config
+        .all_roots()
+        .into_iter()
+        .find(|root| !branches.iter().any(|branch| root.starts_with(branch)))
+    {
+        return Err(ConfigValidationError::new(format!(
+            "tier root '{}' is not inside a branch of the mergerfs union",
+            root.display()
+        )));
+    }
+
+    Ok(())
+}
+
 fn ensure_directory(path: &Path, label: &str) -> Result<(), ConfigValidationError> {
     match fs::metadata(path) {
         Ok(metadata) => {
@@ -237,3 +1065,37 @@ fn ensure_directory(path: &Path, label: &str) -> Result<(), ConfigValidationErro
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_roots_become_hot_and_cold_tiers() {
+        let mut config: Config = serde_json::from_str(
+            r#"{
+                "hot_root": "/media/hot",
+                "cold_root": "/media/cold",
+                "tiers": [{ "name": "cold", "root": "/old/cold" }, { "name": "deep", "root": "/media/deep", "roots": ["/media/deep2"] }]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(config.migrate_legacy_roots());
+        let tiers: Vec<_> = config
+            .tiers
+            .iter()
+            .map(|tier| (tier.name.as_str(), tier.roots.join(",")))
+            .collect();
+        assert_eq!(
+            tiers,
+            vec![
+                ("hot", "/media/hot".to_string()),
+                ("cold", "/media/cold".to_string()),
+                ("deep", "/media/deep,/media/deep2".to_string())
+            ]
+        );
+        assert!(!config.migrate_legacy_roots());
+        assert!(!serde_json::to_string(&config).unwrap().contains("hot_root"));
+    }
+}
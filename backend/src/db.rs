@@ -134,6 +134,222 @@ async fn apply_schema(pool: &DbPool) -> Result<(), sqlx::Error> {
     .execute(pool)
     .await?;
 
+    run_migrations(pool).await?;
+
+    Ok(())
+}
+
+/// Incremental schema changes layered on top of the base tables above.
+/// The applied version is tracked in SQLite's `user_version` pragma, so entries
+/// must only ever be appended; never edit or reorder a released migration.
+const MIGRATIONS: &[&[&str]] = &[
+    // 1: job timing, so queue wait can be told apart from run time
+    &[
+        "ALTER TABLE jobs ADD COLUMN started_at INTEGER",
+        "ALTER TABLE jobs ADD COLUMN finished_at INTEGER",
+        "UPDATE jobs SET finished_at = updated_at WHERE status IN ('success', 'failed')",
+        "CREATE INDEX IF NOT EXISTS idx_jobs_finished_at ON jobs(finished_at)",
+    ],
+    // 2: per-run bookkeeping for retries and the copy strategy in use
+    &[
+        "ALTER TABLE jobs ADD COLUMN bytes_copied_this_run INTEGER",
+        "ALTER TABLE jobs ADD COLUMN attempt INTEGER NOT NULL DEFAULT 0",
+        "ALTER TABLE jobs ADD COLUMN strategy TEXT",
+        "UPDATE jobs SET strategy = 'copy_then_delete'",
+        "UPDATE jobs SET attempt = 1 WHERE status <> 'queued'",
+    ],
+    // 3: client-supplied idempotency keys for job creation
+    &[
+        "ALTER TABLE jobs ADD COLUMN idempotency_key TEXT",
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_jobs_idempotency_key ON jobs(idempotency_key) WHERE idempotency_key IS NOT NULL",
+    ],
+    // 4: show freshness signals and the tiering policy decision log
+    &[
+        "ALTER TABLE shows ADD COLUMN first_seen_at INTEGER",
+        "ALTER TABLE shows ADD COLUMN episodes_added_at INTEGER",
+        "UPDATE shows SET first_seen_at = last_scan",
+        r#"
+        CREATE TABLE IF NOT EXISTS policy_decisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            created_at INTEGER NOT NULL,
+            show_id INTEGER NOT NULL,
+            show_title TEXT,
+            rule_name TEXT NOT NULL,
+            source_location TEXT,
+            target TEXT NOT NULL,
+            action TEXT NOT NULL,
+            job_id INTEGER,
+            reason TEXT NOT NULL
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_policy_decisions_created_at ON policy_decisions(created_at)",
+    ],
+    // 5: pool pins that automatic tiering must respect
+    &["ALTER TABLE shows ADD COLUMN pinned_location TEXT"],
+    // 6: playback history aggregated from Jellyfin, one row per show
+    &[r#"
+        CREATE TABLE IF NOT EXISTS show_activity (
+            show_id INTEGER PRIMARY KEY REFERENCES shows(id) ON DELETE CASCADE,
+            last_played_at INTEGER,
+            play_count INTEGER NOT NULL DEFAULT 0,
+            active_viewers INTEGER NOT NULL DEFAULT 0,
+            synced_at INTEGER NOT NULL
+        )
+        "#],
+    // 7: episode-level prefetch jobs and the files they placed in the hot pool
+    &[
+        "ALTER TABLE jobs ADD COLUMN kind TEXT NOT NULL DEFAULT 'move'",
+        "ALTER TABLE jobs ADD COLUMN files TEXT",
+        r#"
+        CREATE TABLE IF NOT EXISTS prefetched_files (
+            show_id INTEGER NOT NULL REFERENCES shows(id) ON DELETE CASCADE,
+            relative_path TEXT NOT NULL,
+            hot_path TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (show_id, relative_path)
+        )
+        "#,
+    ],
+    // 8: named storage tiers instead of a fixed hot/cold pair; existing rows
+    // are labelled from their paths at startup (see `tiers::relabel_rows`)
+    &[
+        "ALTER TABLE jobs ADD COLUMN source_tier TEXT",
+        "ALTER TABLE jobs ADD COLUMN target_tier TEXT",
+        "ALTER TABLE pool_usage_history RENAME COLUMN pool_type TO tier",
+        "DROP INDEX IF EXISTS idx_pool_usage_type_timestamp",
+        "CREATE INDEX IF NOT EXISTS idx_pool_usage_tier_timestamp ON pool_usage_history(tier, timestamp)",
+    ],
+    // 9: directory fingerprints so scans can skip shows that have not changed
+    &["ALTER TABLE shows ADD COLUMN scan_fingerprint TEXT"],
+    // 10: shows whose folder vanished are flagged rather than deleted
+    &[
+        "ALTER TABLE shows ADD COLUMN missing_since INTEGER",
+        "CREATE INDEX IF NOT EXISTS idx_shows_missing_since ON shows(missing_since)",
+    ],
+    // 11: one row per finished filesystem scan
+    &[
+        r#"
+        CREATE TABLE IF NOT EXISTS scan_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            started_at INTEGER NOT NULL,
+            finished_at INTEGER NOT NULL,
+            full INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            scanned_libraries INTEGER NOT NULL DEFAULT 0,
+            shows_processed INTEGER NOT NULL DEFAULT 0,
+            inserted INTEGER NOT NULL DEFAULT 0,
+            updated INTEGER NOT NULL DEFAULT 0,
+            unchanged INTEGER NOT NULL DEFAULT 0,
+            removed INTEGER NOT NULL DEFAULT 0,
+            purged INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            warnings TEXT
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_scan_runs_started_at ON scan_runs(started_at)",
+    ],
+    // 12: show metadata from tvshow.nfo, with genres and tags as many-to-many
+    &[
+        "ALTER TABLE shows ADD COLUMN original_title TEXT",
+        "ALTER TABLE shows ADD COLUMN year INTEGER",
+        "ALTER TABLE shows ADD COLUMN premiered TEXT",
+        "ALTER TABLE shows ADD COLUMN end_date TEXT",
+        "ALTER TABLE shows ADD COLUMN rating REAL",
+        "ALTER TABLE shows ADD COLUMN series_status TEXT",
+        "ALTER TABLE shows ADD COLUMN studios TEXT",
+        "ALTER TABLE shows ADD COLUMN provider_ids TEXT",
+        r#"
+        CREATE TABLE IF NOT EXISTS genres (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS show_genres (
+            show_id INTEGER NOT NULL REFERENCES shows(id) ON DELETE CASCADE,
+            genre_id INTEGER NOT NULL REFERENCES genres(id) ON DELETE CASCADE,
+            PRIMARY KEY (show_id, genre_id)
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_show_genres_genre_id ON show_genres(genre_id)",
+        r#"
+        CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS show_tags (
+            show_id INTEGER NOT NULL REFERENCES shows(id) ON DELETE CASCADE,
+            tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+            PRIMARY KEY (show_id, tag_id)
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_show_tags_tag_id ON show_tags(tag_id)",
+        // Unchanged folders are skipped by scans; forget fingerprints so every show is read again.
+        "UPDATE shows SET scan_fingerprint = NULL",
+    ],
+    // 13: one row per numbered episode video, with its NFO metadata
+    &[
+        r#"
+        CREATE TABLE IF NOT EXISTS episodes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            show_id INTEGER NOT NULL REFERENCES shows(id) ON DELETE CASCADE,
+            season INTEGER NOT NULL,
+            episode INTEGER NOT NULL,
+            title TEXT,
+            aired TEXT,
+            runtime_minutes INTEGER,
+            relative_path TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            video_codec TEXT,
+            audio_codec TEXT,
+            width INTEGER,
+            height INTEGER,
+            bitrate INTEGER,
+            UNIQUE (show_id, relative_path)
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_episodes_show_season ON episodes(show_id, season, episode)",
+        "UPDATE shows SET scan_fingerprint = NULL",
+    ],
+    // 14: series, movie or other, detected by scans
+    &[
+        "ALTER TABLE shows ADD COLUMN kind TEXT NOT NULL DEFAULT 'series'",
+        "CREATE INDEX IF NOT EXISTS idx_shows_kind ON shows(kind)",
+        "UPDATE shows SET scan_fingerprint = NULL",
+    ],
+    // 15: jobs deferred for playback after their copy keep it for the next pickup
+    &["ALTER TABLE jobs ADD COLUMN copied_at INTEGER"],
+    // 16: lookup of a show's latest policy decision
+    &["CREATE INDEX IF NOT EXISTS idx_policy_decisions_show ON policy_decisions(show_id, id)"],
+];
+
+async fn run_migrations(pool: &DbPool) -> Result<(), sqlx::Error> {
+    let current: i64 = sqlx::query_scalar("PRAGMA user_version")
+        .fetch_one(pool)
+        .await?;
+
+    for (index, statements) in MIGRATIONS.iter().enumerate() {
+        let version = index as i64 + 1;
+        if version <= current {
+            continue;
+        }
+
+        let mut tx = pool.begin().await?;
+        for statement in statements.iter() {
+            sqlx::query(statement).execute(&mut *tx).await?;
+        }
+        // PRAGMA does not accept bound parameters.
+        sqlx::query(&format!("PRAGMA user_version = {version}"))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        info!(version, "Applied database migration");
+    }
+
     Ok(())
 }
 
@@ -243,9 +459,6 @@ async fn create_history_indexes(pool: &DbPool) -> Result<(), sqlx::Error> {
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_pool_usage_timestamp ON pool_usage_history(timestamp);")
         .execute(pool)
         .await?;
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_pool_usage_type_timestamp ON pool_usage_history(pool_type, timestamp);")
-        .execute(pool)
-        .await?;
 
     Ok(())
 }
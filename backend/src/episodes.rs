@@ -0,0 +1,176 @@
+use serde::Serialize;
+use sqlx::{FromRow, SqliteConnection};
+use std::path::Path;
+
+use crate::{
+    db::DbPool,
+    nfo::{self, EpisodeNfo},
+    scanner::EpisodeFile,
+};
+
+/// An episode video found by a scan, with the NFO beside it if there is one.
+#[derive(Debug)]
+pub struct EpisodeCandidate {
+    pub file: EpisodeFile,
+    pub nfo: EpisodeNfo,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct EpisodeRecord {
+    pub id: i64,
+    pub season: i64,
+    pub episode: i64,
+    pub title: Option<String>,
+    pub aired: Option<String>,
+    pub runtime_minutes: Option<i64>,
+    /// Video path relative to the show folder.
+    pub relative_path: String,
+    /// Size of the video plus its subtitles, NFO and thumbnails.
+    pub size_bytes: i64,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub bitrate: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SeasonSummary {
+    pub season: i64,
+    pub episode_count: usize,
+    pub size_bytes: i64,
+}
+
+/// Response of `GET /api/shows/:id/episodes`.
+#[derive(Debug, Serialize)]
+pub struct ShowEpisodes {
+    pub show_id: i64,
+    pub seasons: Vec<SeasonSummary>,
+    pub episodes: Vec<EpisodeRecord>,
+}
+
+/// Pairs each episode video under `show_path` with its NFO.
+pub fn index(show_path: &Path, files: Vec<EpisodeFile>) -> Vec<EpisodeCandidate> {
+    files
+        .into_iter()
+        .map(|file| {
+            let nfo = file
+                .companion_paths
+                .iter()
+                .find(|companion| {
+                    Path::new(companion)
+                        .extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("nfo"))
+                })
+                .and_then(|companion| nfo::read_episode_nfo(&show_path.join(companion)))
+                .unwrap_or_default();
+            EpisodeCandidate { file, nfo }
+        })
+        .collect()
+}
+
+/// Replaces the show's episode rows with `episodes`. Rows of episodes that
+/// are prefetched into the fastest tier are kept, since their files are not
+/// in the show folder while they are away.
+pub async fn replace(
+    conn: &mut SqliteConnection,
+    show_id: i64,
+    episodes: &[EpisodeCandidate],
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        DELETE FROM episodes
+        WHERE show_id = ?1 AND relative_path NOT IN (
+            SELECT relative_path FROM prefetched_files WHERE show_id = ?1
+        )
+        "#,
+    )
+    .bind(show_id)
+    .execute(&mut *conn)
+    .await?;
+
+    for EpisodeCandidate { file, nfo } in episodes {
+        sqlx::query(
+            r#"
+            INSERT INTO episodes (
+                show_id, season, episode, title, aired, runtime_minutes, relative_path,
+                size_bytes, video_codec, audio_codec, width, height, bitrate
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(show_id, relative_path) DO UPDATE SET
+                season = excluded.season,
+                episode = excluded.episode,
+                title = excluded.title,
+                aired = excluded.aired,
+                runtime_minutes = excluded.runtime_minutes,
+                size_bytes = excluded.size_bytes,
+                video_codec = excluded.video_codec,
+                audio_codec = excluded.audio_codec,
+                width = excluded.width,
+                height = excluded.height,
+                bitrate = excluded.bitrate
+            "#,
+        )
+        .bind(show_id)
+        .bind(i64::from(file.season))
+        .bind(i64::from(file.episode))
+        .bind(&nfo.title)
+        .bind(&nfo.aired)
+        .bind(nfo.runtime_minutes)
+        .bind(&file.relative_path)
+        .bind(file.size_bytes)
+        .bind(&nfo.video_codec)
+        .bind(&nfo.audio_codec)
+        .bind(nfo.width)
+        .bind(nfo.height)
+        .bind(nfo.bitrate)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// The show's episodes in season/episode order with per-season totals, or
+/// `None` when the show does not exist.
+pub async fn list(db: &DbPool, show_id: i64) -> Result<Option<ShowEpisodes>, sqlx::Error> {
+    let exists: Option<i64> = sqlx::query_scalar("SELECT id FROM shows WHERE id = ?")
+        .bind(show_id)
+        .fetch_optional(db)
+        .await?;
+    if exists.is_none() {
+        return Ok(None);
+    }
+
+    let episodes = sqlx::query_as::<_, EpisodeRecord>(
+        r#"
+        SELECT id, season, episode, title, aired, runtime_minutes, relative_path, size_bytes,
+               video_codec, audio_codec, width, height, bitrate
+        FROM episodes
+        WHERE show_id = ?
+        ORDER BY season, episode, relative_path
+        "#,
+    )
+    .bind(show_id)
+    .fetch_all(db)
+    .await?;
+
+    let mut seasons: Vec<SeasonSummary> = Vec::new();
+    for episode in &episodes {
+        match seasons.last_mut() {
+            Some(summary) if summary.season == episode.season => {
+                summary.episode_count += 1;
+                summary.size_bytes = summary.size_bytes.saturating_add(episode.size_bytes);
+            }
+            _ => seasons.push(SeasonSummary {
+                season: episode.season,
+                episode_count: 1,
+                size_bytes: episode.size_bytes,
+            }),
+        }
+    }
+
+    Ok(Some(ShowEpisodes {
+        show_id,
+        seasons,
+        episodes,
+    }))
+}
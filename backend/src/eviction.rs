@@ -0,0 +1,270 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::{
+    sync::RwLock,
+    task::JoinHandle,
+    time::{Duration, sleep},
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{self, Config, EvictionConfig},
+    db::DbPool,
+    jobs::{self, JobError},
+    policy::{self, ACTION_FAILED, ACTION_QUEUED, PolicyDecision, ProjectedUsage, ShowFacts},
+    scanner::ScanStatus,
+};
+
+/// Recorded as the rule name in the policy decision log.
+const EVICTION_RULE_NAME: &str = "tier watermark";
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+#[derive(Debug, Default, Serialize)]
+pub struct EvictionRunSummary {
+    pub projected_used_percent: f64,
+    pub planned: usize,
+    pub queued: usize,
+    pub failed: usize,
+}
+
+/// Start the background task that keeps the fastest tier under `Config::eviction`'s watermarks.
+pub fn start_evictor(
+    db: DbPool,
+    config: Arc<RwLock<Config>>,
+    scan_status: Arc<RwLock<ScanStatus>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Starting tier eviction background task");
+
+        loop {
+            let interval_minutes = config.read().await.eviction.interval_minutes.max(1);
+            sleep(Duration::from_secs(u64::from(interval_minutes) * 60)).await;
+
+            let config_snapshot = config.read().await.clone();
+            if !config_snapshot.eviction.enabled || !config::config_is_ready(&config_snapshot) {
+                continue;
+            }
+            if scan_status.read().await.is_running() {
+                info!("Skipping tier eviction while a scan is running");
+                continue;
+            }
+
+            match run_once(&db, &config_snapshot).await {
+                Ok(Some(summary)) if summary.planned > 0 => info!(
+                    projected_used_percent = summary.projected_used_percent,
+                    planned = summary.planned,
+                    queued = summary.queued,
+                    failed = summary.failed,
+                    "Tier eviction finished"
+                ),
+                Ok(_) => {}
+                Err(err) => error!(?err, "Tier eviction failed"),
+            }
+        }
+    })
+}
+
+/// Checks the fastest tier's usage once and queues moves to the next tier if
+/// it is above the high watermark.
+///
+/// Returns `None` when fewer than two tiers are configured or the fastest
+/// tier's usage cannot be read.
+pub async fn run_once(
+    db: &DbPool,
+    config: &Config,
+) -> Result<Option<EvictionRunSummary>, sqlx::Error> {
+    let [source, target, ..] = config.tiers.as_slice() else {
+        return Ok(None);
+    };
+    let Some(usage) = policy::projected_usage(db, source).await? else {
+        warn!(tier = %source.name, "Could not read tier usage; skipping eviction");
+        return Ok(None);
+    };
+
+    let shows = policy::load_show_facts(db).await?;
+    let tiers = (source.name.as_str(), target.name.as_str());
+    let decisions = plan(
+        &config.eviction,
+        usage,
+        tiers,
+        &shows,
+        Utc::now().timestamp(),
+    );
+
+    let mut summary = EvictionRunSummary {
+        projected_used_percent: usage.percent(),
+        planned: decisions.len(),
+        ..EvictionRunSummary::default()
+    };
+
+    for decision in decisions {
+        match jobs::create_move_job(db, config, decision.show_id, &decision.target, None, false)
+            .await
+        {
+            Ok(job) => {
+                info!(
+                    show_id = decision.show_id,
+                    job_id = job.id,
+                    target = %decision.target,
+                    reason = %decision.reason,
+                    "Eviction queued move"
+                );
+                policy::record_decision(db, &decision, ACTION_QUEUED, Some(job.id), None).await?;
+                summary.queued += 1;
+            }
+            Err(JobError::Database(err)) => return Err(err),
+            Err(err) => {
+                warn!(
+                    show_id = decision.show_id,
+                    ?err,
+                    "Eviction could not queue move"
+                );
+                if !policy::latest_decision_failed(db, &decision).await? {
+                    let note = format!("{err:?}");
+                    policy::record_decision(db, &decision, ACTION_FAILED, None, Some(&note))
+                        .await?;
+                }
+                summary.failed += 1;
+            }
+        }
+    }
+
+    Ok(Some(summary))
+}
+
+/// Picks shows in the `(source, target)` tiers' source to move to the target,
+/// highest eviction score first, until the source's projected usage reaches the
+/// low watermark. Plans nothing while usage is at or below the high watermark.
+///
+/// Shows with an active job are skipped because their bytes are already part
+/// of `usage`; shows pinned to the source tier are never evicted.
+pub fn plan(
+    settings: &EvictionConfig,
+    usage: ProjectedUsage,
+    (source, target): (&str, &str),
+    shows: &[ShowFacts],
+    now: i64,
+) -> Vec<PolicyDecision> {
+    let start_percent = usage.percent();
+    if usage.total_bytes == 0 || start_percent <= settings.high_watermark_percent {
+        return Vec::new();
+    }
+
+    let mut candidates: Vec<(f64, &ShowFacts)> = shows
+        .iter()
+        .filter(|show| {
+            show.location.as_deref() == Some(source)
+                && !show.has_active_job
+                && show.pinned_location.as_deref() != Some(source)
+                && show.size_bytes.unwrap_or(0) > 0
+        })
+        .map(|show| (eviction_score(settings, show, now), show))
+        .collect();
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.id.cmp(&b.1.id)));
+
+    let mut remaining = usage;
+    let mut decisions = Vec::new();
+
+    for (score, show) in candidates {
+        if remaining.percent() <= settings.low_watermark_percent {
+            break;
+        }
+
+        let size_bytes = show.size_bytes.unwrap_or(0).max(0);
+        remaining = remaining.shifted(-size_bytes);
+        decisions.push(PolicyDecision {
+            show_id: show.id,
+            show_title: show.title.clone(),
+            rule_name: EVICTION_RULE_NAME.to_string(),
+            source_location: show.location.clone(),
+            target: target.to_string(),
+            size_bytes,
+            reason: format!(
+                "{source} tier at {start_percent:.1}% (high watermark {}%); score {score:.1}",
+                settings.high_watermark_percent
+            ),
+        });
+    }
+
+    decisions
+}
+
+/// Days since the show last saw activity (playback or new episodes), plus its size in GiB,
+/// each scaled by the configured weight.
+fn eviction_score(settings: &EvictionConfig, show: &ShowFacts, now: i64) -> f64 {
+    let last_activity = [
+        show.last_played_at,
+        show.episodes_added_at,
+        show.first_seen_at,
+    ]
+    .into_iter()
+    .flatten()
+    .max()
+    .unwrap_or(0);
+    let idle_days = (now - last_activity).max(0) as f64 / 86400.0;
+    let size_gib = show.size_bytes.unwrap_or(0).max(0) as f64 / GIB;
+
+    idle_days * settings.idle_weight + size_gib * settings.size_weight
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86400;
+
+    fn hot_show(id: i64, size_bytes: i64, last_played_at: i64) -> ShowFacts {
+        ShowFacts {
+            id,
+            title: None,
+            path: format!("/media/hot/{id}"),
+            location: Some("hot".to_string()),
+            size_bytes: Some(size_bytes),
+            first_seen_at: Some(0),
+            episodes_added_at: None,
+            last_played_at: Some(last_played_at),
+            rating: None,
+            pinned_location: None,
+            has_active_job: false,
+        }
+    }
+
+    #[test]
+    fn evicts_idlest_unpinned_shows_down_to_low_watermark() {
+        let now = 100 * DAY;
+        let settings = EvictionConfig {
+            size_weight: 0.0,
+            ..EvictionConfig::default()
+        };
+        let usage = ProjectedUsage {
+            total_bytes: 1_000,
+            used_bytes: 900,
+        };
+
+        let mut pinned = hot_show(1, 300, 0);
+        pinned.pinned_location = Some("hot".to_string());
+        let mut busy = hot_show(2, 300, 0);
+        busy.has_active_job = true;
+        let shows = [
+            pinned,
+            busy,
+            hot_show(3, 100, now - 10 * DAY),
+            hot_show(4, 100, now - 50 * DAY),
+            hot_show(5, 100, now - 30 * DAY),
+        ];
+
+        let tiers = ("hot", "cold");
+        let evicted: Vec<i64> = plan(&settings, usage, tiers, &shows, now)
+            .iter()
+            .map(|decision| decision.show_id)
+            .collect();
+        assert_eq!(evicted, vec![4, 5]);
+
+        let below_high = ProjectedUsage {
+            used_bytes: 850,
+            ..usage
+        };
+        assert!(plan(&settings, below_high, tiers, &shows, now).is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    os::unix::fs::symlink,
+    path::{Path, PathBuf},
+};
+use tracing::warn;
+use walkdir::WalkDir;
+
+use crate::{config::Config, db::DbPool};
+
+/// Outcome of a reconciliation pass over the symlink farm.
+#[derive(Debug, Default, Serialize)]
+pub struct FarmReport {
+    /// Changes were only reported, not made.
+    pub dry_run: bool,
+    /// Shows that should have a link.
+    pub links: usize,
+    pub created: Vec<String>,
+    pub retargeted: Vec<String>,
+    /// Stale links pointing into a tier root or at nothing.
+    pub removed: Vec<String>,
+    pub conflicts: Vec<FarmConflict>,
+}
+
+/// A link that could not be put in place.
+#[derive(Debug, Serialize)]
+pub struct FarmConflict {
+    pub link: String,
+    pub reason: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LinkChange {
+    Unchanged,
+    Created,
+    Retargeted,
+}
+
+/// The stable path of the show at `show_path`: its path below its tier root,
+/// placed under the farm root. `None` when stable paths are disabled or the
+/// show is outside every tier.
+pub fn link_for(config: &Config, show_path: &Path) -> Option<PathBuf> {
+    let farm_root = config.stable_paths.farm_root_path()?;
+    let (_, root) = config.locate(show_path)?;
+    let relative = show_path.strip_prefix(root).ok()?;
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+    Some(farm_root.join(relative))
+}
+
+/// Points `link` at `target`. An existing link is swapped by renaming a fresh
+/// one over it, so readers never see the path missing. Anything at `link`
+/// that is not a symlink is left alone and reported as an error.
+pub fn point_link(link: &Path, target: &Path) -> io::Result<LinkChange> {
+    let change = inspect_link(link, target)?;
+    if change == LinkChange::Unchanged {
+        return Ok(change);
+    }
+
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file_name = link
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "link has no file name"))?;
+    let staging = link.with_file_name(format!(".{}.jellymover-tmp", file_name.to_string_lossy()));
+    match fs::remove_file(&staging) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    symlink(target, &staging)?;
+    if let Err(err) = fs::rename(&staging, link) {
+        let _ = fs::remove_file(&staging);
+        return Err(err);
+    }
+
+    Ok(change)
+}
+
+/// Brings the farm in line with the shows table: every show inside a tier
+/// gets a link to its current folder, and links left behind by shows that
+/// are gone are removed. Symlinks pointing outside the tier roots and regular
+/// files are never touched.
+///
+/// Returns `None` when stable paths are disabled.
+pub async fn reconcile(
+    db: &DbPool,
+    config: &Config,
+    dry_run: bool,
+) -> Result<Option<FarmReport>, sqlx::Error> {
+    let Some(farm_root) = config.stable_paths.farm_root_path() else {
+        return Ok(None);
+    };
+
+    let shows: Vec<String> = sqlx::query_scalar(
+        "SELECT path FROM shows WHERE location IS NOT NULL AND missing_since IS NULL ORDER BY id",
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut report = FarmReport {
+        dry_run,
+        ..FarmReport::default()
+    };
+    let mut expected: HashMap<PathBuf, PathBuf> = HashMap::new();
+    for show_path in shows {
+        let target = PathBuf::from(&show_path);
+        let Some(link) = link_for(config, &target) else {
+            continue;
+        };
+        if let Some(existing) = expected.get(&link) {
+            report.conflicts.push(FarmConflict {
+                link: link.display().to_string(),
+                reason: format!(
+                    "both '{}' and '{show_path}' map to this link",
+                    existing.display()
+                ),
+            });
+            continue;
+        }
+        expected.insert(link, target);
+    }
+    report.links = expected.len();
+
+    for (link, target) in &expected {
+        let result = if dry_run {
+            inspect_link(link, target)
+        } else {
+            point_link(link, target)
+        };
+        let link = link.display().to_string();
+        match result {
+            Ok(LinkChange::Unchanged) => {}
+            Ok(LinkChange::Created) => report.created.push(link),
+            Ok(LinkChange::Retargeted) => report.retargeted.push(link),
+            Err(err) => report.conflicts.push(FarmConflict {
+                link,
+                reason: err.to_string(),
+            }),
+        }
+    }
+
+    let roots = config.all_roots();
+    let managed: HashSet<&PathBuf> = expected.keys().collect();
+    for entry in WalkDir::new(&farm_root).min_depth(1).into_iter() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn!(%err, "Failed to read entry in stable path farm");
+                continue;
+            }
+        };
+        if !entry.path_is_symlink() || managed.contains(&entry.path().to_path_buf()) {
+            continue;
+        }
+        let Ok(target) = fs::read_link(entry.path()) else {
+            continue;
+        };
+        let stale = !entry.path().exists() || roots.iter().any(|root| target.starts_with(root));
+        if !stale {
+            continue;
+        }
+        if !dry_run && let Err(err) = fs::remove_file(entry.path()) {
+            report.conflicts.push(FarmConflict {
+                link: entry.path().display().to_string(),
+                reason: err.to_string(),
+            });
+            continue;
+        }
+        report.removed.push(entry.path().display().to_string());
+    }
+
+    report.created.sort();
+    report.retargeted.sort();
+    report.removed.sort();
+    Ok(Some(report))
+}
+
+/// What `point_link` would do, without changing anything.
+fn inspect_link(link: &Path, target: &Path) -> io::Result<LinkChange> {
+    match fs::symlink_metadata(link) {
+        Ok(metadata) if !metadata.file_type().is_symlink() => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "path exists and is not a symlink",
+        )),
+        Ok(_) if fs::read_link(link)? == target => Ok(LinkChange::Unchanged),
+        Ok(_) => Ok(LinkChange::Retargeted),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(LinkChange::Created),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn point_link_creates_and_swaps_links() {
+        let dir = TempDir::new("farm");
+        let base = dir.path();
+        let (hot, cold) = (base.join("hot/Show"), base.join("cold/Show"));
+        fs::create_dir_all(&hot).unwrap();
+        fs::create_dir_all(&cold).unwrap();
+        let link = base.join("farm/tv/Show");
+
+        assert_eq!(point_link(&link, &cold).unwrap(), LinkChange::Created);
+        assert_eq!(point_link(&link, &cold).unwrap(), LinkChange::Unchanged);
+        assert_eq!(inspect_link(&link, &hot).unwrap(), LinkChange::Retargeted);
+        assert_eq!(point_link(&link, &hot).unwrap(), LinkChange::Retargeted);
+        assert_eq!(fs::read_link(&link).unwrap(), hot);
+        assert!(point_link(&base.join("hot"), &cold).is_err());
+    }
+}
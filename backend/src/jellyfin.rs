@@ -1,8 +1,12 @@
 use crate::config::Config;
+use chrono::{DateTime, SecondsFormat, Utc};
 use reqwest::StatusCode;
-use serde::Serialize;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use tracing::{error, info};
 
+/// Page size used when walking paged `/Items` endpoints.
+const ITEMS_PAGE_SIZE: usize = 1000;
+
 #[derive(Debug)]
 pub enum JellyfinError {
     NotConfigured,
@@ -111,6 +115,125 @@ impl JellyfinClient {
             Err(JellyfinError::UnexpectedStatus(response.status()))
         }
     }
+
+    pub async fn list_users(&self) -> Result<Vec<JellyfinUser>, JellyfinError> {
+        let url = format!("{}/Users", self.base_url);
+        self.get_json(&url, &[]).await
+    }
+
+    /// Every series in the server's libraries, with its folder path.
+    pub async fn list_series(&self) -> Result<Vec<SeriesItem>, JellyfinError> {
+        let url = format!("{}/Items", self.base_url);
+        self.get_all_items(
+            &url,
+            &[
+                ("IncludeItemTypes", "Series"),
+                ("Recursive", "true"),
+                ("Fields", "Path"),
+                ("EnableImages", "false"),
+            ],
+        )
+        .await
+    }
+
+    /// Every episode with the given user's `UserData` (play count, last played date).
+    pub async fn list_user_episodes(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<EpisodeItem>, JellyfinError> {
+        let url = format!("{}/Users/{}/Items", self.base_url, user_id);
+        self.get_all_items(
+            &url,
+            &[
+                ("IncludeItemTypes", "Episode"),
+                ("Recursive", "true"),
+                ("EnableUserData", "true"),
+                ("EnableImages", "false"),
+            ],
+        )
+        .await
+    }
+
+    /// User-attributed activity log entries recorded at or after `since`.
+    pub async fn activity_log(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ActivityLogEntry>, JellyfinError> {
+        let url = format!("{}/System/ActivityLog/Entries", self.base_url);
+        let min_date = since.to_rfc3339_opts(SecondsFormat::Secs, true);
+        self.get_all_items(&url, &[("minDate", &min_date), ("hasUserId", "true")])
+            .await
+    }
+
+    /// The user's "Next Up" episodes: the next unwatched episode of each series in progress.
+    pub async fn next_up(&self, user_id: &str) -> Result<Vec<NextUpEpisode>, JellyfinError> {
+        let url = format!("{}/Shows/NextUp", self.base_url);
+        self.get_all_items(
+            &url,
+            &[
+                ("UserId", user_id),
+                ("Fields", "Path"),
+                ("EnableImages", "false"),
+            ],
+        )
+        .await
+    }
+
+    /// File paths of everything currently playing in any session.
+    pub async fn now_playing_paths(&self) -> Result<Vec<String>, JellyfinError> {
+        let url = format!("{}/Sessions", self.base_url);
+        let sessions: Vec<SessionInfo> = self.get_json(&url, &[]).await?;
+        Ok(sessions
+            .into_iter()
+            .filter_map(|session| session.now_playing_item?.path)
+            .collect())
+    }
+
+    async fn get_all_items<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>, JellyfinError> {
+        let limit = ITEMS_PAGE_SIZE.to_string();
+        let mut items = Vec::new();
+
+        loop {
+            let start_index = items.len().to_string();
+            let mut page_query = query.to_vec();
+            page_query.push(("StartIndex", &start_index));
+            page_query.push(("Limit", &limit));
+
+            let page: ItemsPage<T> = self.get_json(url, &page_query).await?;
+            let fetched = page.items.len();
+            items.extend(page.items);
+
+            if fetched < ITEMS_PAGE_SIZE || items.len() >= page.total_record_count {
+                return Ok(items);
+            }
+        }
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, JellyfinError> {
+        info!(endpoint = %url, "Fetching Jellyfin data");
+
+        let response = self
+            .http
+            .get(url)
+            .query(query)
+            .header("X-Emby-Token", &self.api_key)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(response.json().await?)
+        } else {
+            Err(JellyfinError::UnexpectedStatus(response.status()))
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -139,6 +262,79 @@ pub struct TaskInfo {
     pub key: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ItemsPage<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+    #[serde(default)]
+    total_record_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct JellyfinUser {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SeriesItem {
+    pub id: String,
+    pub name: Option<String>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct EpisodeItem {
+    pub id: String,
+    pub series_id: Option<String>,
+    pub user_data: Option<UserItemData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct UserItemData {
+    #[serde(default)]
+    pub play_count: i64,
+    /// ISO 8601 timestamp, e.g. `2024-05-01T20:15:33.1234567Z`.
+    pub last_played_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct NextUpEpisode {
+    pub path: Option<String>,
+    /// Season number.
+    pub parent_index_number: Option<u32>,
+    /// Episode number within the season.
+    pub index_number: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SessionInfo {
+    now_playing_item: Option<NowPlayingItem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NowPlayingItem {
+    path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ActivityLogEntry {
+    #[serde(rename = "Type")]
+    pub kind: Option<String>,
+    pub item_id: Option<String>,
+    pub user_id: Option<String>,
+    /// ISO 8601 timestamp of the event.
+    pub date: String,
+}
+
 #[derive(Debug, Serialize, serde::Deserialize, PartialEq)]
 pub enum TaskState {
     Idle,
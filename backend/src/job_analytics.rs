@@ -0,0 +1,180 @@
+use serde::Serialize;
+use sqlx::FromRow;
+
+use crate::{config::Config, db::DbPool};
+
+/// Per-bucket statistics over finished (successful or failed) move jobs.
+#[derive(Debug, Serialize, FromRow)]
+pub struct JobAnalyticsBucket {
+    pub bucket_start: i64,
+    pub completed_count: i64,
+    pub failed_count: i64,
+    pub failure_rate: f64,
+    /// Bytes moved to a faster tier.
+    pub bytes_promoted: i64,
+    /// Bytes moved to a slower tier.
+    pub bytes_demoted: i64,
+    pub mean_duration_secs: Option<f64>,
+    pub p50_duration_secs: Option<i64>,
+    pub p95_duration_secs: Option<i64>,
+    pub mean_throughput_bytes_per_sec: Option<f64>,
+}
+
+pub fn bucket_seconds(bucket: Option<&str>) -> Option<i64> {
+    match bucket {
+        Some("hour") => Some(3600),
+        None | Some("day") => Some(24 * 3600),
+        Some("week") => Some(7 * 24 * 3600),
+        _ => None,
+    }
+}
+
+/// Aggregates finished jobs into fixed-width time buckets keyed by finish time.
+///
+/// Durations measure run time (`started_at` to `finished_at`); jobs recorded
+/// before those columns existed fall back to `created_at`/`updated_at`.
+/// Direction compares the positions of the job's source and target tiers in
+/// the current `tiers` list; jobs involving a tier no longer configured count
+/// toward neither total.
+pub async fn collect_buckets(
+    db: &DbPool,
+    config: &Config,
+    bucket_secs: i64,
+    since: i64,
+) -> Result<Vec<JobAnalyticsBucket>, sqlx::Error> {
+    let ranks: serde_json::Map<String, serde_json::Value> = config
+        .tiers
+        .iter()
+        .enumerate()
+        .map(|(rank, tier)| (tier.name.clone(), rank.into()))
+        .collect();
+    let ranks = serde_json::Value::Object(ranks).to_string();
+
+    sqlx::query_as::<_, JobAnalyticsBucket>(
+        r#"
+        WITH finished AS (
+            SELECT
+                (COALESCE(finished_at, updated_at) / ?1) * ?1 AS bucket_start,
+                status,
+                COALESCE(total_bytes, 0) AS bytes,
+                COALESCE(finished_at, updated_at) - COALESCE(started_at, created_at) AS duration,
+                CASE
+                    WHEN json_extract(?3, '$."' || target_tier || '"')
+                       < json_extract(?3, '$."' || source_tier || '"') THEN 'promoted'
+                    WHEN json_extract(?3, '$."' || target_tier || '"')
+                       > json_extract(?3, '$."' || source_tier || '"') THEN 'demoted'
+                END AS direction
+            FROM jobs
+            WHERE status IN ('success', 'failed')
+              AND COALESCE(finished_at, updated_at) >= ?2
+        ),
+        ranked AS (
+            SELECT
+                bucket_start,
+                duration,
+                ROW_NUMBER() OVER (PARTITION BY bucket_start ORDER BY duration) AS position,
+                COUNT(*) OVER (PARTITION BY bucket_start) AS total
+            FROM finished
+            WHERE status = 'success' AND duration >= 0
+        ),
+        percentiles AS (
+            SELECT
+                bucket_start,
+                MIN(CASE WHEN position >= 0.50 * total THEN duration END) AS p50_duration_secs,
+                MIN(CASE WHEN position >= 0.95 * total THEN duration END) AS p95_duration_secs
+            FROM ranked
+            GROUP BY bucket_start
+        )
+        SELECT
+            f.bucket_start,
+            SUM(f.status = 'success') AS completed_count,
+            SUM(f.status = 'failed') AS failed_count,
+            CAST(SUM(f.status = 'failed') AS REAL) / COUNT(*) AS failure_rate,
+            COALESCE(SUM(CASE WHEN f.status = 'success' AND f.direction = 'promoted' THEN f.bytes END), 0) AS bytes_promoted,
+            COALESCE(SUM(CASE WHEN f.status = 'success' AND f.direction = 'demoted' THEN f.bytes END), 0) AS bytes_demoted,
+            AVG(CASE WHEN f.status = 'success' AND f.duration >= 0 THEN f.duration END) AS mean_duration_secs,
+            p.p50_duration_secs,
+            p.p95_duration_secs,
+            AVG(CASE WHEN f.status = 'success' AND f.duration > 0 THEN CAST(f.bytes AS REAL) / f.duration END) AS mean_throughput_bytes_per_sec
+        FROM finished f
+        LEFT JOIN percentiles p ON p.bucket_start = f.bucket_start
+        GROUP BY f.bucket_start
+        ORDER BY f.bucket_start ASC
+        "#,
+    )
+    .bind(bucket_secs)
+    .bind(since)
+    .bind(ranks)
+    .fetch_all(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::TierConfig, test_support::test_pool};
+
+    const DAY: i64 = 24 * 3600;
+
+    #[tokio::test]
+    async fn aggregates_finished_jobs_per_bucket() {
+        let (_dir, db) = test_pool().await;
+        let tier = |name: &str| TierConfig {
+            name: name.to_string(),
+            ..TierConfig::default()
+        };
+        let config = Config {
+            tiers: vec![tier("hot"), tier("cold")],
+            ..Config::default()
+        };
+        sqlx::query("INSERT INTO shows (id, title, path) VALUES (1, 'Show', '/media/show')")
+            .execute(&db)
+            .await
+            .unwrap();
+        for (status, source, target, bytes, started_at, finished_at) in [
+            // Finished before `since`, so left out.
+            ("success", "cold", "hot", 999, DAY - 100, DAY - 50),
+            ("success", "cold", "hot", 100, 2 * DAY, 2 * DAY + 10),
+            ("success", "hot", "cold", 60, 2 * DAY, 2 * DAY + 30),
+            ("failed", "cold", "hot", 500, 2 * DAY, 2 * DAY + 5),
+            ("success", "cold", "gone", 70, 3 * DAY, 3 * DAY + 20),
+        ] {
+            sqlx::query(
+                "INSERT INTO jobs (show_id, source_path, destination_path, status, total_bytes, \
+                 created_at, updated_at, started_at, finished_at, source_tier, target_tier) \
+                 VALUES (1, '/a', '/b', ?, ?, 0, ?, ?, ?, ?, ?)",
+            )
+            .bind(status)
+            .bind(bytes)
+            .bind(finished_at)
+            .bind(started_at)
+            .bind(finished_at)
+            .bind(source)
+            .bind(target)
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+
+        let buckets = collect_buckets(&db, &config, DAY, DAY).await.unwrap();
+        assert_eq!(buckets.len(), 2);
+
+        let first = &buckets[0];
+        assert_eq!(first.bucket_start, 2 * DAY);
+        assert_eq!(first.completed_count, 2);
+        assert_eq!(first.failed_count, 1);
+        assert!((first.failure_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(first.bytes_promoted, 100);
+        assert_eq!(first.bytes_demoted, 60);
+        assert_eq!(first.mean_duration_secs, Some(20.0));
+        assert_eq!(first.p50_duration_secs, Some(10));
+        assert_eq!(first.p95_duration_secs, Some(30));
+        assert_eq!(first.mean_throughput_bytes_per_sec, Some(6.0));
+
+        // A tier that is no longer configured counts toward neither direction.
+        let second = &buckets[1];
+        assert_eq!(second.bucket_start, 3 * DAY);
+        assert_eq!(second.completed_count, 1);
+        assert_eq!((second.bytes_promoted, second.bytes_demoted), (0, 0));
+    }
+}
@@ -0,0 +1,310 @@
+use chrono::Utc;
+use flate2::{Compression, write::GzEncoder};
+use serde::Serialize;
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    sync::RwLock,
+    task::{JoinHandle, spawn_blocking},
+    time::{Duration, interval},
+};
+use tracing::{error, info};
+
+use crate::{
+    config::Config,
+    db::DbPool,
+    jobs::{self, JobFilter, JobRecord, JobSort, STATUS_FAILED, STATUS_SUCCESS},
+};
+
+/// An open archive file and its path.
+type Archive = (PathBuf, GzEncoder<File>);
+
+const PRUNE_INTERVAL_HOURS: u64 = 6;
+const PRUNE_BATCH_SIZE: i64 = 500;
+
+#[derive(Debug, Serialize)]
+pub struct PruneSummary {
+    pub deleted: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_path: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum PruneError {
+    Database(sqlx::Error),
+    Archive(io::Error),
+}
+
+impl fmt::Display for PruneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PruneError::Database(err) => write!(f, "Database error: {err}"),
+            PruneError::Archive(err) => write!(f, "Failed to write job archive: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for PruneError {}
+
+impl From<sqlx::Error> for PruneError {
+    fn from(value: sqlx::Error) -> Self {
+        PruneError::Database(value)
+    }
+}
+
+impl From<io::Error> for PruneError {
+    fn from(value: io::Error) -> Self {
+        PruneError::Archive(value)
+    }
+}
+
+/// Start the background task that enforces `Config::job_retention`.
+pub fn start_pruner(db: DbPool, config: Arc<RwLock<Config>>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Starting job retention background task");
+
+        let mut prune_interval = interval(Duration::from_secs(PRUNE_INTERVAL_HOURS * 3600));
+
+        loop {
+            prune_interval.tick().await;
+            let config_snapshot = config.read().await.clone();
+            apply_retention(&db, &config_snapshot).await;
+        }
+    })
+}
+
+async fn apply_retention(db: &DbPool, config: &Config) {
+    let retention = &config.job_retention;
+    let archive_dir = archive_dir(config);
+    let now = Utc::now().timestamp();
+
+    for (status, days) in [
+        (STATUS_SUCCESS, retention.success_days),
+        (STATUS_FAILED, retention.failed_days),
+    ] {
+        let Some(days) = days else { continue };
+        let cutoff = now - i64::from(days) * 86400;
+
+        match prune_jobs(db, &[status], cutoff, archive_dir.as_deref()).await {
+            Ok(summary) if summary.deleted > 0 => {
+                info!(
+                    status,
+                    deleted = summary.deleted,
+                    archive = summary.archive_path.as_deref().unwrap_or("-"),
+                    "Pruned old jobs"
+                );
+            }
+            Ok(_) => {}
+            Err(err) => error!(%err, status, "Failed to prune old jobs"),
+        }
+    }
+}
+
+pub fn archive_dir(config: &Config) -> Option<PathBuf> {
+    let trimmed = config.job_retention.archive_dir.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(trimmed))
+    }
+}
+
+/// Deletes finished jobs with the given statuses that finished before `before`.
+///
+/// When `archive_dir` is set, every row is appended to a gzip-compressed
+/// JSON-lines file there before it is deleted; a failed archive write aborts
+/// the prune so no history is lost. Archive writes run on the blocking pool.
+pub async fn prune_jobs(
+    db: &DbPool,
+    statuses: &[&str],
+    before: i64,
+    archive_dir: Option<&Path>,
+) -> Result<PruneSummary, PruneError> {
+    // An empty status list would match every job, including active ones.
+    if statuses.is_empty() {
+        return Ok(PruneSummary {
+            deleted: 0,
+            archive_path: None,
+        });
+    }
+
+    let filter = JobFilter {
+        statuses: statuses.iter().map(|status| status.to_string()).collect(),
+        finished_to: Some(before),
+        ..JobFilter::default()
+    };
+
+    let mut archive: Option<Archive> = None;
+    let deleted = delete_matching(db, &filter, archive_dir, &mut archive).await?;
+    Ok(PruneSummary {
+        deleted,
+        archive_path: close_archive(archive).await?,
+    })
+}
+
+/// Deletes every finished job of the given shows, archiving them like
+/// `prune_jobs` does, so purging the shows keeps their history.
+pub async fn prune_show_jobs(
+    db: &DbPool,
+    show_ids: &[i64],
+    archive_dir: Option<&Path>,
+) -> Result<PruneSummary, PruneError> {
+    let mut archive: Option<Archive> = None;
+    let mut deleted = 0_u64;
+
+    for &show_id in show_ids {
+        let filter = JobFilter {
+            statuses: vec![STATUS_SUCCESS.to_string(), STATUS_FAILED.to_string()],
+            show_id: Some(show_id),
+            ..JobFilter::default()
+        };
+        deleted += delete_matching(db, &filter, archive_dir, &mut archive).await?;
+    }
+
+    Ok(PruneSummary {
+        deleted,
+        archive_path: close_archive(archive).await?,
+    })
+}
+
+/// Deletes the jobs matching `filter` in batches, appending each batch to
+/// `archive` first when `archive_dir` is set.
+async fn delete_matching(
+    db: &DbPool,
+    filter: &JobFilter,
+    archive_dir: Option<&Path>,
+    archive: &mut Option<Archive>,
+) -> Result<u64, PruneError> {
+    let mut deleted = 0_u64;
+
+    loop {
+        let (batch, _) =
+            jobs::list_jobs(db, filter, JobSort::Finished, false, PRUNE_BATCH_SIZE, 0).await?;
+        if batch.is_empty() {
+            break;
+        }
+
+        let ids: Vec<i64> = batch.iter().map(|record| record.id).collect();
+        if let Some(dir) = archive_dir {
+            let dir = dir.to_path_buf();
+            let open = archive.take();
+            *archive = Some(
+                spawn_blocking(move || append_to_archive(open, &dir, &batch))
+                    .await
+                    .map_err(io::Error::other)??,
+            );
+        }
+
+        deleted += jobs::delete_jobs(db, &ids).await?;
+    }
+
+    Ok(deleted)
+}
+
+/// Finishes the gzip stream and returns the archive's path.
+async fn close_archive(archive: Option<Archive>) -> Result<Option<String>, PruneError> {
+    let Some((path, encoder)) = archive else {
+        return Ok(None);
+    };
+    spawn_blocking(move || encoder.finish())
+        .await
+        .map_err(io::Error::other)??;
+    Ok(Some(path.display().to_string()))
+}
+
+/// Writes `records` to `archive`, opening a new archive in `dir` if needed.
+fn append_to_archive(
+    archive: Option<Archive>,
+    dir: &Path,
+    records: &[JobRecord],
+) -> Result<Archive, io::Error> {
+    let (path, mut encoder) = match archive {
+        Some(archive) => archive,
+        None => open_archive(dir)?,
+    };
+    for record in records {
+        serde_json::to_writer(&mut encoder, record).map_err(io::Error::from)?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.flush()?;
+    Ok((path, encoder))
+}
+
+fn open_archive(dir: &Path) -> Result<Archive, io::Error> {
+    fs::create_dir_all(dir)?;
+    let file_name = format!("jobs-{}.jsonl.gz", Utc::now().format("%Y%m%dT%H%M%S%.3f"));
+    let path = dir.join(file_name);
+    let file = File::create(&path)?;
+    Ok((path, GzEncoder::new(file, Compression::default())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TempDir, test_pool};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[tokio::test]
+    async fn prunes_only_old_finished_jobs_and_archives_them() {
+        let (_db_dir, db) = test_pool().await;
+        let archive_dir = TempDir::new("archive");
+        sqlx::query("INSERT INTO shows (id, title, path) VALUES (1, 'Show', '/media/show')")
+            .execute(&db)
+            .await
+            .unwrap();
+        for (id, status, finished_at) in [
+            (1, "success", Some(100)),
+            (2, "failed", Some(200)),
+            (3, "success", Some(5_000)),
+            (4, "running", None),
+            (5, "queued", None),
+        ] {
+            sqlx::query(
+                "INSERT INTO jobs (id, show_id, source_path, destination_path, status, \
+                 created_at, updated_at, finished_at) VALUES (?, 1, '/a', '/b', ?, 0, 0, ?)",
+            )
+            .bind(id)
+            .bind(status)
+            .bind(finished_at)
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+
+        let summary = prune_jobs(
+            &db,
+            &[STATUS_SUCCESS, STATUS_FAILED],
+            1_000,
+            Some(archive_dir.path()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.deleted, 2);
+
+        let remaining: Vec<i64> = sqlx::query_scalar("SELECT id FROM jobs ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec![3, 4, 5]);
+
+        let mut lines = String::new();
+        GzDecoder::new(File::open(summary.archive_path.unwrap()).unwrap())
+            .read_to_string(&mut lines)
+            .unwrap();
+        let archived: Vec<i64> = lines
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["id"]
+                    .as_i64()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(archived, vec![1, 2]);
+    }
+}
@@ -1,9 +1,16 @@
-use crate::{config::Config, db::DbPool};
+use crate::{
+    config::{Config, PlaybackGuardMode, TierConfig},
+    db::DbPool,
+    farm, mergerfs, playback, policy,
+    quotas::{QuotaExceeded, QuotaTracker},
+    tiers,
+};
 use chrono::Utc;
 use serde::Serialize;
-use sqlx::FromRow;
+use sqlx::{FromRow, QueryBuilder, Sqlite};
 use std::{
-    io,
+    collections::HashSet,
+    fmt, io,
     path::{Path, PathBuf},
     sync::Arc,
     time::Instant,
@@ -19,8 +26,45 @@ use walkdir::WalkDir;
 
 const STATUS_QUEUED: &str = "queued";
 const STATUS_RUNNING: &str = "running";
-const STATUS_SUCCESS: &str = "success";
-const STATUS_FAILED: &str = "failed";
+/// Active, but parked until Jellyfin stops streaming the show.
+pub const STATUS_WAITING_FOR_PLAYBACK: &str = "waiting_for_playback";
+pub const STATUS_SUCCESS: &str = "success";
+pub const STATUS_FAILED: &str = "failed";
+
+const STRATEGY_COPY_THEN_DELETE: &str = "copy_then_delete";
+
+/// Moves a whole show folder between tiers.
+pub const KIND_MOVE: &str = "move";
+/// Moves individual episode files of a show into its prefetch folder in the fastest tier.
+pub const KIND_PREFETCH: &str = "prefetch";
+/// Moves prefetched episode files back to the show folder in its own tier.
+pub const KIND_PREFETCH_RETURN: &str = "prefetch_return";
+
+const JOB_COLUMNS: &str = r#"
+    id,
+    show_id,
+    source_path,
+    destination_path,
+    status,
+    progress_bytes,
+    total_bytes,
+    speed_bytes_per_sec,
+    eta_seconds,
+    error_message,
+    created_at,
+    updated_at,
+    started_at,
+    finished_at,
+    finished_at - started_at AS duration_seconds,
+    bytes_copied_this_run,
+    attempt,
+    strategy,
+    kind,
+    files,
+    source_tier,
+    target_tier,
+    copied_at
+"#;
 
 #[derive(Debug, Serialize, FromRow, Clone)]
 pub struct JobRecord {
@@ -36,19 +80,143 @@ pub struct JobRecord {
     pub error_message: Option<String>,
     pub created_at: Option<i64>,
     pub updated_at: Option<i64>,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+    pub duration_seconds: Option<i64>,
+    pub bytes_copied_this_run: Option<i64>,
+    pub attempt: i64,
+    pub strategy: Option<String>,
+    pub kind: String,
+    pub source_tier: Option<String>,
+    pub target_tier: Option<String>,
+}
+
+/// Optional constraints for `list_jobs`; unset fields do not filter.
+#[derive(Debug, Default)]
+pub struct JobFilter {
+    pub statuses: Vec<String>,
+    pub show_id: Option<i64>,
+    pub source_tier: Option<String>,
+    pub target_tier: Option<String>,
+    pub created_from: Option<i64>,
+    pub created_to: Option<i64>,
+    pub finished_from: Option<i64>,
+    pub finished_to: Option<i64>,
+    pub error_contains: Option<String>,
+    pub strategy: Option<String>,
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum JobSort {
+    Created,
+    Updated,
+    Started,
+    Finished,
+    Duration,
+    Size,
+    Status,
+    Attempt,
+}
+
+impl JobSort {
+    /// `None` for unknown keys; a missing key sorts by creation time.
+    pub fn parse(value: Option<&str>) -> Option<Self> {
+        Some(match value {
+            None | Some("created") => JobSort::Created,
+            Some("updated") => JobSort::Updated,
+            Some("started") => JobSort::Started,
+            Some("finished") => JobSort::Finished,
+            Some("duration") => JobSort::Duration,
+            Some("size") => JobSort::Size,
+            Some("status") => JobSort::Status,
+            Some("attempt") => JobSort::Attempt,
+            _ => return None,
+        })
+    }
+
+    fn column(self) -> &'static str {
+        match self {
+            JobSort::Created => "created_at",
+            JobSort::Updated => "updated_at",
+            JobSort::Started => "started_at",
+            JobSort::Finished => "finished_at",
+            JobSort::Duration => "duration_seconds",
+            JobSort::Size => "total_bytes",
+            JobSort::Status => "status",
+            JobSort::Attempt => "attempt",
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum JobError {
     ShowNotFound,
+    /// The show's folder is gone from disk.
+    ShowMissing,
     InvalidTarget,
     AlreadyInLocation,
-    MissingRoot(&'static str),
+    /// The named tier has no root configured.
+    MissingRoot(String),
     PathMismatch,
+    /// The show already has a queued or running job (carries that job's id).
+    JobAlreadyActive(i64),
+    /// An `Idempotency-Key` was reused for a different show or target tier.
+    IdempotencyKeyMismatch,
+    /// The show is pinned to another tier and the move was not forced (carries the pin).
+    ShowPinned(String),
+    /// Jellyfin is streaming the show and the playback guard is set to skip.
+    ShowInUse,
+    /// Moving the show would exceed its library's quota in the target tier.
+    QuotaExceeded(QuotaExceeded),
+    /// The move would break the target tier's limits (carries the tier name).
+    TierFull(String),
+    /// The show's folder also exists on another mergerfs branch (carries that path).
+    ShowSplit(String),
     Database(sqlx::Error),
     Io(std::io::Error),
 }
 
+impl fmt::Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobError::ShowNotFound => write!(f, "Show not found"),
+            JobError::ShowMissing => write!(f, "Show folder is missing from disk"),
+            JobError::InvalidTarget => write!(f, "Target is not a configured tier"),
+            JobError::AlreadyInLocation => write!(f, "Show is already in the requested tier"),
+            JobError::MissingRoot(tier) => write!(f, "Tier {tier} has no root"),
+            JobError::PathMismatch => write!(f, "Show path is not within a configured tier"),
+            JobError::JobAlreadyActive(job_id) => {
+                write!(f, "Show already has an active job ({job_id})")
+            }
+            JobError::IdempotencyKeyMismatch => {
+                write!(
+                    f,
+                    "Idempotency-Key was already used for a different show or target"
+                )
+            }
+            JobError::ShowPinned(pinned) => write!(f, "Show is pinned to {pinned}"),
+            JobError::ShowInUse => write!(
+                f,
+                "Show is being streamed in Jellyfin and the playback guard skips such moves"
+            ),
+            JobError::ShowSplit(other) => {
+                write!(f, "Show folder also exists on another branch at {other}")
+            }
+            JobError::QuotaExceeded(exceeded) => write!(
+                f,
+                "Move would exceed the {} quota of {}",
+                exceeded.tier, exceeded.library
+            ),
+            JobError::TierFull(tier) => write!(f, "Move would exceed the limits of tier {tier}"),
+            JobError::Database(err) => write!(f, "Database error: {err}"),
+            JobError::Io(err) => write!(f, "Filesystem error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for JobError {}
+
 impl From<sqlx::Error> for JobError {
     fn from(value: sqlx::Error) -> Self {
         JobError::Database(value)
@@ -66,6 +234,8 @@ struct ShowRow {
     id: i64,
     path: String,
     size_bytes: Option<i64>,
+    pinned_location: Option<String>,
+    missing_since: Option<i64>,
 }
 
 #[derive(Debug, FromRow, Clone)]
@@ -82,6 +252,20 @@ struct JobRow {
     error_message: Option<String>,
     created_at: Option<i64>,
     updated_at: Option<i64>,
+    started_at: Option<i64>,
+    finished_at: Option<i64>,
+    duration_seconds: Option<i64>,
+    bytes_copied_this_run: Option<i64>,
+    attempt: i64,
+    strategy: Option<String>,
+    kind: String,
+    /// JSON array of paths relative to the source folder, for prefetch jobs.
+    files: Option<String>,
+    source_tier: Option<String>,
+    target_tier: Option<String>,
+    /// Set when the copy finished but the switch-over was deferred for
+    /// playback; the next pickup skips straight to it.
+    copied_at: Option<i64>,
 }
 
 impl From<JobRow> for JobRecord {
@@ -99,20 +283,44 @@ impl From<JobRow> for JobRecord {
             error_message: value.error_message,
             created_at: value.created_at,
             updated_at: value.updated_at,
+            started_at: value.started_at,
+            finished_at: value.finished_at,
+            duration_seconds: value.duration_seconds,
+            bytes_copied_this_run: value.bytes_copied_this_run,
+            attempt: value.attempt,
+            strategy: value.strategy,
+            kind: value.kind,
+            source_tier: value.source_tier,
+            target_tier: value.target_tier,
         }
     }
 }
 
+/// Queues a move of `show_id` to `target`.
+///
+/// A show may only have one queued or running job at a time. When an
+/// `idempotency_key` is given and a job was already created with it, that job
+/// is returned unchanged instead of queuing another one. Moving a show away
+/// from the tier it is pinned to requires `force`.
 pub async fn create_move_job(
     db: &DbPool,
     config: &Config,
     show_id: i64,
     target: &str,
+    idempotency_key: Option<&str>,
+    force: bool,
 ) -> Result<JobRecord, JobError> {
-    let normalized_target = normalize_target(target).ok_or(JobError::InvalidTarget)?;
+    let target_tier = normalize_target(config, target).ok_or(JobError::InvalidTarget)?;
+
+    if let Some(key) = idempotency_key
+        && let Some(existing) = find_job_by_idempotency_key(db, key).await?
+    {
+        return replay_idempotent_job(existing, show_id, &target_tier.name);
+    }
+
     let show = sqlx::query_as::<_, ShowRow>(
         r#"
-        SELECT id, path, size_bytes
+        SELECT id, path, size_bytes, pinned_location, missing_since
         FROM shows
         WHERE id = ?
         "#,
@@ -122,32 +330,53 @@ pub async fn create_move_job(
     .await?
     .ok_or(JobError::ShowNotFound)?;
 
-    let hot_root = trimmed_root(&config.hot_root).ok_or(JobError::MissingRoot("hot_root"))?;
-    let cold_root = trimmed_root(&config.cold_root).ok_or(JobError::MissingRoot("cold_root"))?;
+    if show.missing_since.is_some() {
+        return Err(JobError::ShowMissing);
+    }
+
+    if let Some(other) = split_copy(db, config, &show.path).await? {
+        return Err(JobError::ShowSplit(other));
+    }
+
+    if target_tier.root_paths().is_empty() {
+        return Err(JobError::MissingRoot(target_tier.name.clone()));
+    }
 
     let show_path = PathBuf::from(&show.path);
-    let (current_location, current_root) = detect_location(&show_path, &hot_root, &cold_root)?;
+    let (current_tier, current_root) = config.locate(&show_path).ok_or(JobError::PathMismatch)?;
 
-    if current_location == normalized_target {
+    if current_tier.name == target_tier.name {
         return Err(JobError::AlreadyInLocation);
     }
 
-    let destination_root = if normalized_target == "hot" {
-        &hot_root
-    } else {
-        &cold_root
-    };
+    if let Some(pinned) = show.pinned_location
+        && pinned != target_tier.name
+        && !force
+    {
+        return Err(JobError::ShowPinned(pinned));
+    }
+
+    let total_bytes = show.size_bytes.unwrap_or(0).max(0);
+    QuotaTracker::load(db, config)
+        .await?
+        .check(&show.path, &target_tier.name, total_bytes)
+        .map_err(JobError::QuotaExceeded)?;
+    check_tier_limits(db, target_tier, total_bytes).await?;
 
     let relative = show_path
-        .strip_prefix(current_root)
+        .strip_prefix(&current_root)
         .map_err(|_| JobError::PathMismatch)?;
-    let destination_path = destination_root.join(relative);
+    let target_root = tiers::choose_root(db, target_tier, relative)
+        .await?
+        .ok_or_else(|| JobError::MissingRoot(target_tier.name.clone()))?;
+    let destination_path = target_root.join(relative);
 
-    let total_bytes = show.size_bytes.unwrap_or(0).max(0);
     let now = Utc::now().timestamp();
 
     let source_path_str = show.path.clone();
-    sqlx::query(
+    // The NOT EXISTS guard makes the duplicate check and the insert a single
+    // statement, so concurrent requests cannot both queue a job for the show.
+    let insert_result = sqlx::query(
         r#"
         INSERT INTO jobs (
             show_id,
@@ -160,8 +389,16 @@ pub async fn create_move_job(
             eta_seconds,
             error_message,
             created_at,
-            updated_at
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            updated_at,
+            strategy,
+            idempotency_key,
+            source_tier,
+            target_tier
+        )
+        SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
+        WHERE NOT EXISTS (
+            SELECT 1 FROM jobs WHERE show_id = ? AND status IN (?, ?, ?)
+        )
         "#,
     )
     .bind(show.id)
@@ -175,19 +412,58 @@ pub async fn create_move_job(
     .bind::<Option<String>>(None)
     .bind(now)
     .bind(now)
+    .bind(STRATEGY_COPY_THEN_DELETE)
+    .bind(idempotency_key)
+    .bind(&current_tier.name)
+    .bind(&target_tier.name)
+    .bind(show.id)
+    .bind(STATUS_QUEUED)
+    .bind(STATUS_RUNNING)
+    .bind(STATUS_WAITING_FOR_PLAYBACK)
     .execute(db)
-    .await?;
+    .await;
+
+    let insert_result = match insert_result {
+        Ok(result) => result,
+        // Lost a race against a concurrent request carrying the same key.
+        Err(sqlx::Error::Database(err)) if err.is_unique_violation() => {
+            if let Some(key) = idempotency_key
+                && let Some(existing) = find_job_by_idempotency_key(db, key).await?
+            {
+                return replay_idempotent_job(existing, show_id, &target_tier.name);
+            }
+            return Err(JobError::Database(sqlx::Error::Database(err)));
+        }
+        Err(err) => return Err(err.into()),
+    };
 
-    let job_id: i64 = sqlx::query_scalar("SELECT last_insert_rowid()")
+    if insert_result.rows_affected() == 0 {
+        let active_id: i64 = sqlx::query_scalar(
+            r#"
+            SELECT id FROM jobs
+            WHERE show_id = ? AND status IN (?, ?, ?)
+            ORDER BY created_at
+            LIMIT 1
+            "#,
+        )
+        .bind(show.id)
+        .bind(STATUS_QUEUED)
+        .bind(STATUS_RUNNING)
+        .bind(STATUS_WAITING_FOR_PLAYBACK)
         .fetch_one(db)
         .await?;
+        return Err(JobError::JobAlreadyActive(active_id));
+    }
+
+    let job_id = insert_result.last_insert_rowid();
 
     info!(
         job_id,
         show_id = show.id,
         source = %source_path_str,
         destination = %destination_path.display(),
-        target = normalized_target,
+        source_tier = %current_tier.name,
+        target_tier = %target_tier.name,
         "Queued move job"
     );
 
@@ -196,62 +472,296 @@ pub async fn create_move_job(
         .ok_or(JobError::Database(sqlx::Error::RowNotFound))
 }
 
+async fn find_job_by_idempotency_key(
+    db: &DbPool,
+    key: &str,
+) -> Result<Option<JobRecord>, sqlx::Error> {
+    sqlx::query_as::<_, JobRecord>(&format!(
+        r#"
+        SELECT {JOB_COLUMNS}
+        FROM jobs
+        WHERE idempotency_key = ?
+        "#,
+    ))
+    .bind(key)
+    .fetch_optional(db)
+    .await
+}
+
+fn replay_idempotent_job(
+    existing: JobRecord,
+    show_id: i64,
+    target: &str,
+) -> Result<JobRecord, JobError> {
+    if existing.show_id != show_id || existing.target_tier.as_deref() != Some(target) {
+        return Err(JobError::IdempotencyKeyMismatch);
+    }
+    info!(
+        job_id = existing.id,
+        show_id, "Returning existing job for repeated idempotency key"
+    );
+    Ok(existing)
+}
+
+/// Returns one page of jobs matching `filter` together with the total match count.
 pub async fn list_jobs(
     db: &DbPool,
+    filter: &JobFilter,
+    sort: JobSort,
+    descending: bool,
     limit: i64,
     offset: i64,
-) -> Result<Vec<JobRecord>, sqlx::Error> {
-    sqlx::query_as::<_, JobRecord>(
+) -> Result<(Vec<JobRecord>, i64), sqlx::Error> {
+    let mut count_query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM jobs");
+    push_job_filter(&mut count_query, filter);
+    let total: i64 = count_query.build_query_scalar().fetch_one(db).await?;
+
+    let mut query = QueryBuilder::<Sqlite>::new(format!("SELECT {JOB_COLUMNS} FROM jobs"));
+    push_job_filter(&mut query, filter);
+    let direction = if descending { "DESC" } else { "ASC" };
+    // id breaks ties so paging stays stable when many rows share a timestamp.
+    query.push(format!(
+        " ORDER BY {} {direction} NULLS LAST, id {direction}",
+        sort.column()
+    ));
+    query.push(" LIMIT ").push_bind(limit);
+    query.push(" OFFSET ").push_bind(offset);
+
+    let records = query.build_query_as::<JobRecord>().fetch_all(db).await?;
+    Ok((records, total))
+}
+
+fn push_job_filter<'a>(query: &mut QueryBuilder<'a, Sqlite>, filter: &'a JobFilter) {
+    let mut separator = " WHERE ";
+
+    if !filter.statuses.is_empty() {
+        query.push(separator).push("status IN (");
+        let mut statuses = query.separated(", ");
+        for status in &filter.statuses {
+            statuses.push_bind(status);
+        }
+        query.push(")");
+        separator = " AND ";
+    }
+    if let Some(show_id) = filter.show_id {
+        query.push(separator).push("show_id = ").push_bind(show_id);
+        separator = " AND ";
+    }
+    if let Some(tier) = &filter.source_tier {
+        query.push(separator).push("source_tier = ").push_bind(tier);
+        separator = " AND ";
+    }
+    if let Some(tier) = &filter.target_tier {
+        query.push(separator).push("target_tier = ").push_bind(tier);
+        separator = " AND ";
+    }
+    if let Some(from) = filter.created_from {
+        query.push(separator).push("created_at >= ").push_bind(from);
+        separator = " AND ";
+    }
+    if let Some(to) = filter.created_to {
+        query.push(separator).push("created_at < ").push_bind(to);
+        separator = " AND ";
+    }
+    if let Some(from) = filter.finished_from {
+        query
+            .push(separator)
+            .push("finished_at >= ")
+            .push_bind(from);
+        separator = " AND ";
+    }
+    if let Some(to) = filter.finished_to {
+        query.push(separator).push("finished_at < ").push_bind(to);
+        separator = " AND ";
+    }
+    if let Some(needle) = &filter.error_contains {
+        query
+            .push(separator)
+            .push("instr(lower(error_message), lower(")
+            .push_bind(needle)
+            .push(")) > 0");
+        separator = " AND ";
+    }
+    if let Some(strategy) = &filter.strategy {
+        query
+            .push(separator)
+            .push("strategy = ")
+            .push_bind(strategy);
+        separator = " AND ";
+    }
+    if let Some(kind) = &filter.kind {
+        query.push(separator).push("kind = ").push_bind(kind);
+    }
+}
+
+/// Deletes the given jobs, returning how many rows went away.
+pub async fn delete_jobs(db: &DbPool, ids: &[i64]) -> Result<u64, sqlx::Error> {
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut query = QueryBuilder::<Sqlite>::new("DELETE FROM jobs WHERE id IN (");
+    let mut separated = query.separated(", ");
+    for id in ids {
+        separated.push_bind(*id);
+    }
+    query.push(")");
+
+    let result = query.build().execute(db).await?;
+    Ok(result.rows_affected())
+}
+
+/// Another show row for the same folder on a different mergerfs branch. The
+/// union merges both copies, so moving one of them would leave Jellyfin reading
+/// from two tiers; such moves are refused until one copy is removed.
+async fn split_copy(
+    db: &DbPool,
+    config: &Config,
+    show_path: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let Some(branch_paths) = mergerfs::union_path_for(config, Path::new(show_path))
+        .and_then(|union_path| mergerfs::branch_paths_for(config, &union_path))
+    else {
+        return Ok(None);
+    };
+
+    for path in branch_paths {
+        let path = path.to_string_lossy();
+        if path == show_path {
+            continue;
+        }
+        let found: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM shows WHERE path = ? AND missing_since IS NULL")
+                .bind(path.as_ref())
+                .fetch_optional(db)
+                .await?;
+        if found.is_some() {
+            return Ok(Some(path.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// One side of a file transfer: a tier and the show's folder inside it.
+#[derive(Debug, Clone, Copy)]
+pub struct TransferEnd<'a> {
+    pub tier: &'a str,
+    pub dir: &'a Path,
+}
+
+/// Queues a `KIND_PREFETCH` or `KIND_PREFETCH_RETURN` job that moves `files`
+/// (relative paths) from `source.dir` to the same relative paths under
+/// `destination.dir`. Like show moves, only one active job per show is allowed.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_prefetch_job(
+    db: &DbPool,
+    config: &Config,
+    show_id: i64,
+    kind: &'static str,
+    source: TransferEnd<'_>,
+    destination: TransferEnd<'_>,
+    files: &[String],
+    total_bytes: i64,
+) -> Result<JobRecord, JobError> {
+    let pinned: Option<String> =
+        sqlx::query_scalar("SELECT pinned_location FROM shows WHERE id = ?")
+            .bind(show_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or(JobError::ShowNotFound)?;
+    if let Some(pinned) = pinned
+        && pinned != destination.tier
+    {
+        return Err(JobError::ShowPinned(pinned));
+    }
+
+    // Returns only give back space in the fastest tier, so only prefetches
+    // are held to the quotas.
+    if kind == KIND_PREFETCH {
+        QuotaTracker::load(db, config)
+            .await?
+            .check(&source.dir.to_string_lossy(), destination.tier, total_bytes)
+            .map_err(JobError::QuotaExceeded)?;
+    }
+
+    let files_json = serde_json::to_string(files).map_err(io::Error::from)?;
+    let now = Utc::now().timestamp();
+
+    let insert_result = sqlx::query(
         r#"
-        SELECT
-            id,
-            show_id,
-            source_path,
-            destination_path,
-            status,
-            progress_bytes,
-            total_bytes,
-            speed_bytes_per_sec,
-            eta_seconds,
-            error_message,
-            created_at,
-            updated_at
-        FROM jobs
-        ORDER BY created_at DESC
-        LIMIT ? OFFSET ?
+        INSERT INTO jobs (
+            show_id, source_path, destination_path, status, progress_bytes, total_bytes,
+            speed_bytes_per_sec, eta_seconds, created_at, updated_at, strategy, kind, files,
+            source_tier, target_tier
+        )
+        SELECT ?, ?, ?, ?, 0, ?, 0, 0, ?, ?, ?, ?, ?, ?, ?
+        WHERE NOT EXISTS (
+            SELECT 1 FROM jobs WHERE show_id = ? AND status IN (?, ?, ?)
+        )
         "#,
     )
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(db)
-    .await
+    .bind(show_id)
+    .bind(source.dir.to_string_lossy().to_string())
+    .bind(destination.dir.to_string_lossy().to_string())
+    .bind(STATUS_QUEUED)
+    .bind(total_bytes)
+    .bind(now)
+    .bind(now)
+    .bind(STRATEGY_COPY_THEN_DELETE)
+    .bind(kind)
+    .bind(files_json)
+    .bind(source.tier)
+    .bind(destination.tier)
+    .bind(show_id)
+    .bind(STATUS_QUEUED)
+    .bind(STATUS_RUNNING)
+    .bind(STATUS_WAITING_FOR_PLAYBACK)
+    .execute(db)
+    .await?;
+
+    if insert_result.rows_affected() == 0 {
+        let active_id: i64 = sqlx::query_scalar(
+            "SELECT id FROM jobs WHERE show_id = ? AND status IN (?, ?, ?) ORDER BY created_at LIMIT 1",
+        )
+        .bind(show_id)
+        .bind(STATUS_QUEUED)
+        .bind(STATUS_RUNNING)
+        .bind(STATUS_WAITING_FOR_PLAYBACK)
+        .fetch_one(db)
+        .await?;
+        return Err(JobError::JobAlreadyActive(active_id));
+    }
+
+    let job_id = insert_result.last_insert_rowid();
+    info!(
+        job_id,
+        show_id,
+        kind,
+        files = files.len(),
+        destination = %destination.dir.display(),
+        "Queued prefetch job"
+    );
+
+    get_job(db, job_id)
+        .await?
+        .ok_or(JobError::Database(sqlx::Error::RowNotFound))
 }
 
 pub async fn get_job(db: &DbPool, id: i64) -> Result<Option<JobRecord>, sqlx::Error> {
-    sqlx::query_as::<_, JobRecord>(
+    sqlx::query_as::<_, JobRecord>(&format!(
         r#"
-        SELECT
-            id,
-            show_id,
-            source_path,
-            destination_path,
-            status,
-            progress_bytes,
-            total_bytes,
-            speed_bytes_per_sec,
-            eta_seconds,
-            error_message,
-            created_at,
-            updated_at
+        SELECT {JOB_COLUMNS}
         FROM jobs
         WHERE id = ?
         "#,
-    )
+    ))
     .bind(id)
     .fetch_optional(db)
     .await
 }
 
+/// Whether a job is queued or running. Jobs parked until playback ends do not
+/// count; scans skip their copies instead.
 pub async fn has_active_jobs(db: &DbPool) -> Result<bool, sqlx::Error> {
     let count: i64 = sqlx::query_scalar(
         r#"
@@ -267,6 +777,50 @@ pub async fn has_active_jobs(db: &DbPool) -> Result<bool, sqlx::Error> {
     Ok(count > 0)
 }
 
+/// Whether an active job copies into or out of the folder at `path`.
+pub async fn has_active_job_at(db: &DbPool, path: &str) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM jobs
+        WHERE status IN (?, ?, ?) AND (source_path = ? OR destination_path = ?)
+        "#,
+    )
+    .bind(STATUS_QUEUED)
+    .bind(STATUS_RUNNING)
+    .bind(STATUS_WAITING_FOR_PLAYBACK)
+    .bind(path)
+    .bind(path)
+    .fetch_one(db)
+    .await?;
+    Ok(count > 0)
+}
+
+/// Bytes that active (queued, running or deferred) jobs will still take out of / add to the pool
+/// rooted at `prefix` (see `root_prefix`), as `(outgoing, incoming)`.
+///
+/// Sources are only deleted once a copy completes, so outgoing counts the full
+/// job size; incoming counts what has not been copied yet.
+pub async fn pending_transfer_bytes(db: &DbPool, prefix: &str) -> Result<(i64, i64), sqlx::Error> {
+    sqlx::query_as::<_, (i64, i64)>(
+        r#"
+        SELECT
+            COALESCE(SUM(CASE WHEN substr(source_path, 1, length(?1)) = ?1
+                THEN COALESCE(total_bytes, 0) END), 0),
+            COALESCE(SUM(CASE WHEN substr(destination_path, 1, length(?1)) = ?1
+                THEN MAX(COALESCE(total_bytes, 0) - COALESCE(progress_bytes, 0), 0) END), 0)
+        FROM jobs
+        WHERE status IN (?2, ?3, ?4)
+        "#,
+    )
+    .bind(prefix)
+    .bind(STATUS_QUEUED)
+    .bind(STATUS_RUNNING)
+    .bind(STATUS_WAITING_FOR_PLAYBACK)
+    .fetch_one(db)
+    .await
+}
+
 pub fn start_worker(
     db: DbPool,
     config: Arc<RwLock<Config>>,
@@ -279,16 +833,52 @@ pub fn start_worker(
                 break;
             }
 
-            match fetch_next_job(&db).await {
+            let config_snapshot = config.read().await.clone();
+            let recheck_seconds = i64::from(config_snapshot.playback_guard.recheck_seconds);
+
+            match fetch_next_job(&db, recheck_seconds).await {
                 Ok(Some(job)) => {
-                    let config_snapshot = config.read().await.clone();
+                    // A copied job the guard now skips goes through `execute_job`,
+                    // which discards its copy.
+                    let gate = if job.copied_at.is_some()
+                        && config_snapshot.playback_guard.mode == PlaybackGuardMode::Skip
+                    {
+                        Ok(true)
+                    } else {
+                        playback_gate(&db, &job, &config_snapshot).await
+                    };
+                    match gate {
+                        Ok(true) => {}
+                        Ok(false) => continue,
+                        Err(err) => {
+                            error!(?err, job_id = job.id, "Failed to hold job for playback");
+                            if wait_for_shutdown(Duration::from_secs(5), &mut shutdown).await {
+                                info!("Shutdown requested; stopping job worker");
+                                break;
+                            }
+                            continue;
+                        }
+                    }
+
+                    let job = match claim_job(&db, job).await {
+                        Ok(job) => job,
+                        Err(err) => {
+                            error!(?err, "Job worker failed to claim job");
+                            if wait_for_shutdown(Duration::from_secs(5), &mut shutdown).await {
+                                info!("Shutdown requested; stopping job worker");
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+
                     if let Err(err) = execute_job(job.clone(), &db, &config_snapshot).await {
                         error!(?err, job_id = job.id, "Move job failed");
                         if let Err(update_err) = finalize_job_status(
                             &db,
                             job.id,
                             STATUS_FAILED,
-                            Some(format!("{err:?}")),
+                            Some(err.to_string()),
                             None,
                         )
                         .await
@@ -332,87 +922,299 @@ async fn wait_for_shutdown(duration: Duration, shutdown: &mut watch::Receiver<bo
     }
 }
 
-async fn fetch_next_job(db: &DbPool) -> Result<Option<JobRow>, sqlx::Error> {
-    let job = sqlx::query_as::<_, JobRow>(
+/// Next job to work on: interrupted running jobs first, then queued jobs and
+/// deferred jobs whose playback re-check is due, oldest first.
+async fn fetch_next_job(db: &DbPool, recheck_seconds: i64) -> Result<Option<JobRow>, sqlx::Error> {
+    sqlx::query_as::<_, JobRow>(&format!(
         r#"
-        SELECT
-            id,
-            show_id,
-            source_path,
-            destination_path,
-            status,
-            progress_bytes,
-            total_bytes,
-            speed_bytes_per_sec,
-            eta_seconds,
-            error_message,
-            created_at,
-            updated_at
+        SELECT {JOB_COLUMNS}
         FROM jobs
         WHERE status IN (?, ?)
+           OR (status = ? AND updated_at <= ?)
         ORDER BY CASE status WHEN ? THEN 0 ELSE 1 END, created_at
         LIMIT 1
         "#,
-    )
+    ))
     .bind(STATUS_QUEUED)
     .bind(STATUS_RUNNING)
+    .bind(STATUS_WAITING_FOR_PLAYBACK)
+    .bind(Utc::now().timestamp() - recheck_seconds)
     .bind(STATUS_RUNNING)
     .fetch_optional(db)
+    .await
+}
+
+/// Applies the playback guard before a job starts. Returns `false` when the
+/// show is being streamed and the job was deferred or skipped instead.
+async fn playback_gate(db: &DbPool, job: &JobRow, config: &Config) -> Result<bool, sqlx::Error> {
+    if !playback::show_in_use(config, &job.source_path).await {
+        return Ok(true);
+    }
+
+    if config.playback_guard.mode == PlaybackGuardMode::Skip {
+        info!(
+            job_id = job.id,
+            show_id = job.show_id,
+            "Skipping move of show being streamed"
+        );
+        finalize_job_status(
+            db,
+            job.id,
+            STATUS_FAILED,
+            Some(JobError::ShowInUse.to_string()),
+            None,
+        )
+        .await?;
+    } else {
+        info!(
+            job_id = job.id,
+            show_id = job.show_id,
+            "Deferring move of show being streamed"
+        );
+        mark_waiting_for_playback(db, job.id).await?;
+    }
+    Ok(false)
+}
+
+async fn mark_waiting_for_playback(db: &DbPool, job_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE jobs
+        SET status = ?, updated_at = ?, speed_bytes_per_sec = 0, eta_seconds = 0
+        WHERE id = ?
+        "#,
+    )
+    .bind(STATUS_WAITING_FOR_PLAYBACK)
+    .bind(Utc::now().timestamp())
+    .bind(job_id)
+    .execute(db)
     .await?;
+    Ok(())
+}
 
-    if let Some(mut job) = job {
-        if job.status != STATUS_RUNNING {
-            let now = Utc::now().timestamp();
-            sqlx::query(
-                r#"
-                UPDATE jobs
-                SET status = ?, updated_at = ?, error_message = NULL
-                WHERE id = ?
-                "#,
-            )
+async fn claim_job(db: &DbPool, mut job: JobRow) -> Result<JobRow, sqlx::Error> {
+    let now = Utc::now().timestamp();
+    // A job that already started before it was parked for playback carries on
+    // with that attempt.
+    if job.status == STATUS_WAITING_FOR_PLAYBACK && job.started_at.is_some() {
+        sqlx::query("UPDATE jobs SET status = ?, updated_at = ? WHERE id = ?")
             .bind(STATUS_RUNNING)
             .bind(now)
             .bind(job.id)
             .execute(db)
             .await?;
-            job.status = STATUS_RUNNING.to_string();
-        }
-        Ok(Some(job))
-    } else {
-        Ok(None)
+        job.status = STATUS_RUNNING.to_string();
+        return Ok(job);
     }
-}
-
-async fn execute_job(job: JobRow, db: &DbPool, config: &Config) -> Result<(), JobError> {
-    let source_path = PathBuf::from(&job.source_path);
-    let destination_path = PathBuf::from(&job.destination_path);
-    let total_bytes = job.total_bytes.unwrap_or(0).max(0);
 
-    info!(
-        job_id = job.id,
-        show_id = job.show_id,
-        source = %source_path.display(),
-        destination = %destination_path.display(),
+    // Every other pickup is a fresh attempt: a job still marked running here
+    // was interrupted (e.g. by a restart) and is being resumed.
+    sqlx::query(
+        r#"
+        UPDATE jobs
+        SET status = ?, updated_at = ?, started_at = ?, finished_at = NULL,
+            bytes_copied_this_run = 0, attempt = attempt + 1, error_message = NULL
+        WHERE id = ?
+        "#,
+    )
+    .bind(STATUS_RUNNING)
+    .bind(now)
+    .bind(now)
+    .bind(job.id)
+    .execute(db)
+    .await?;
+    job.status = STATUS_RUNNING.to_string();
+    job.started_at = Some(now);
+    job.finished_at = None;
+    job.duration_seconds = None;
+    job.bytes_copied_this_run = Some(0);
+    job.attempt += 1;
+    Ok(job)
+}
+
+async fn execute_job(job: JobRow, db: &DbPool, config: &Config) -> Result<(), JobError> {
+    if job.kind != KIND_MOVE {
+        return execute_file_transfer(job, db, config).await;
+    }
+
+    let source_path = PathBuf::from(&job.source_path);
+    let destination_path = PathBuf::from(&job.destination_path);
+    let total_bytes = job.total_bytes.unwrap_or(0).max(0);
+
+    info!(
+        job_id = job.id,
+        show_id = job.show_id,
+        source = %source_path.display(),
+        destination = %destination_path.display(),
+        attempt = job.attempt,
         "Starting move job"
     );
 
-    match fs::remove_dir_all(&destination_path).await {
-        Ok(_) => {}
-        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+    // Prefetched episodes were moved out of the source into the show's folder in
+    // the fastest tier and exist nowhere else. When that folder is the
+    // destination they are already in place, so the leftovers of earlier
+    // attempts are overwritten instead of cleaned; otherwise they are copied
+    // into the destination along with the rest of the show.
+    let prefetched: Vec<(String, String)> =
+        sqlx::query_as("SELECT relative_path, hot_path FROM prefetched_files WHERE show_id = ?")
+            .bind(job.show_id)
+            .fetch_all(db)
+            .await?;
+    let prefetch_in_place = !prefetched.is_empty()
+        && prefetched
+            .iter()
+            .all(|(_, hot_path)| Path::new(hot_path) == destination_path);
+    // A job re-claimed after waiting for playback already holds the full copy.
+    let copied = match job.copied_at {
+        Some(_) => job.progress_bytes.unwrap_or(0),
+        None => {
+            let copied = copy_show_folder(
+                &job,
+                db,
+                &source_path,
+                &destination_path,
+                !prefetch_in_place,
+            )
+            .await?;
+            if prefetch_in_place {
+                copied
+            } else {
+                copied.saturating_add(copy_prefetched_files(&prefetched, &destination_path).await?)
+            }
+        }
+    };
+
+    match source_idle(&job, db, config).await {
+        Ok(true) => {}
+        Ok(false) => return Ok(()),
         Err(err) => {
-            warn!(
+            if matches!(err, JobError::ShowInUse) && !prefetch_in_place {
+                discard_copy(&destination_path).await;
+            }
+            return Err(err);
+        }
+    }
+
+    let final_progress = copied.min(total_bytes);
+    let new_location = detect_location(config, &destination_path)?.name.as_str();
+    let destination_string = destination_path.to_string_lossy().to_string();
+    let updated_at = Utc::now().timestamp();
+
+    let transaction_result: Result<(), JobError> = async {
+        let mut tx = db.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE shows
+            SET path = ?, location = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&destination_string)
+        .bind(new_location)
+        .bind(job.show_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // The destination now holds the whole show, prefetched episodes included.
+        sqlx::query("DELETE FROM prefetched_files WHERE show_id = ?")
+            .bind(job.show_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = ?, error_message = NULL, progress_bytes = ?, eta_seconds = 0, speed_bytes_per_sec = 0, updated_at = ?, finished_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(STATUS_SUCCESS)
+        .bind(final_progress)
+        .bind(updated_at)
+        .bind(updated_at)
+        .bind(job.id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+    .await;
+
+    if let Err(err) = transaction_result {
+        error!(
+            ?err,
+            job_id = job.id,
+            show_id = job.show_id,
+            source = %source_path.display(),
+            destination = %destination_path.display(),
+            "Failed to finalize move job transaction"
+        );
+        return Err(err);
+    }
+
+    // Swap the stable path over before the source goes away, so Jellyfin keeps
+    // reading through the link. A failure here is repaired by reconciliation.
+    if let Some(link) = farm::link_for(config, &destination_path) {
+        match farm::point_link(&link, &destination_path) {
+            Ok(_) => info!(job_id = job.id, link = %link.display(), "Retargeted stable path"),
+            Err(err) => warn!(
                 ?err,
-                destination = %destination_path.display(),
-                "Failed to clean existing destination directory before move"
-            );
+                job_id = job.id,
+                link = %link.display(),
+                "Failed to retarget stable path after move"
+            ),
         }
     }
-    fs::create_dir_all(&destination_path).await?;
+
+    if let Err(err) = fs::remove_dir_all(&source_path).await {
+        warn!(
+            ?err,
+            job_id = job.id,
+            show_id = job.show_id,
+            source = %source_path.display(),
+            "Failed to delete source directory after move"
+        );
+    }
+    if !prefetch_in_place {
+        remove_prefetched_files(&prefetched).await;
+    }
+
+    info!(job_id = job.id, show_id = job.show_id, "Move job completed");
+    Ok(())
+}
+
+/// Copies the show folder into the destination, reporting progress as it goes.
+/// Returns the bytes copied across all attempts.
+async fn copy_show_folder(
+    job: &JobRow,
+    db: &DbPool,
+    source_path: &Path,
+    destination_path: &Path,
+    clean_destination: bool,
+) -> Result<i64, JobError> {
+    let total_bytes = job.total_bytes.unwrap_or(0).max(0);
+    if clean_destination {
+        match fs::remove_dir_all(destination_path).await {
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                warn!(
+                    ?err,
+                    destination = %destination_path.display(),
+                    "Failed to clean existing destination directory before move"
+                );
+            }
+        }
+    }
+    fs::create_dir_all(destination_path).await?;
 
     let mut copied = job.progress_bytes.unwrap_or(0);
+    let mut copied_this_run: i64 = 0;
     let start = Instant::now();
 
-    for entry in WalkDir::new(&source_path).into_iter() {
+    for entry in WalkDir::new(source_path).into_iter() {
         let entry = match entry {
             Ok(e) => e,
             Err(err) => {
@@ -429,7 +1231,7 @@ async fn execute_job(job: JobRow, db: &DbPool, config: &Config) -> Result<(), Jo
         }
 
         let relative = path
-            .strip_prefix(&source_path)
+            .strip_prefix(source_path)
             .map_err(|_| JobError::PathMismatch)?;
         let target_path = destination_path.join(relative);
 
@@ -449,10 +1251,11 @@ async fn execute_job(job: JobRow, db: &DbPool, config: &Config) -> Result<(), Jo
             .unwrap_or(0)
             .min(i64::MAX as u64) as i64;
         copied = copied.saturating_add(bytes);
+        copied_this_run = copied_this_run.saturating_add(bytes);
         let progress = copied.min(total_bytes);
         let elapsed = start.elapsed().as_secs_f64();
         let speed = if elapsed > 0.0 {
-            (copied as f64 / elapsed) as i64
+            (copied_this_run as f64 / elapsed) as i64
         } else {
             0
         };
@@ -463,79 +1266,262 @@ async fn execute_job(job: JobRow, db: &DbPool, config: &Config) -> Result<(), Jo
             0
         };
 
-        update_job_progress(db, job.id, progress, speed, eta).await?;
+        update_job_progress(db, job.id, progress, copied_this_run, speed, eta).await?;
     }
 
-    let final_progress = copied.min(total_bytes);
-    let new_location = resolve_location_from_path(&destination_path, config)?;
-    let destination_string = destination_path.to_string_lossy().to_string();
-    let updated_at = Utc::now().timestamp();
+    Ok(copied)
+}
 
-    let transaction_result: Result<(), JobError> = async {
-        let mut tx = db.begin().await?;
+/// Copies a show's prefetched episodes from its prefetch folder into the
+/// destination of a move. Returns the bytes copied.
+async fn copy_prefetched_files(
+    prefetched: &[(String, String)],
+    destination_path: &Path,
+) -> Result<i64, JobError> {
+    let mut copied: i64 = 0;
+    for (relative, hot_path) in prefetched {
+        let target = destination_path.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let bytes = fs::copy(Path::new(hot_path).join(relative), &target).await?;
+        copied = copied.saturating_add(bytes.min(i64::MAX as u64) as i64);
+    }
+    Ok(copied)
+}
 
-        sqlx::query(
-            r#"
-            UPDATE shows
-            SET path = ?, location = ?
-            WHERE id = ?
-            "#,
-        )
-        .bind(&destination_string)
-        .bind(new_location)
-        .bind(job.show_id)
-        .execute(&mut *tx)
-        .await?;
+/// Deletes prefetched episodes once a move has taken them along, and the
+/// prefetch folders they leave empty.
+async fn remove_prefetched_files(prefetched: &[(String, String)]) {
+    let mut folders = HashSet::new();
+    for (relative, hot_path) in prefetched {
+        let file = Path::new(hot_path).join(relative);
+        match fs::remove_file(&file).await {
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => warn!(?err, file = %file.display(), "Failed to delete prefetched file"),
+        }
+        folders.insert(hot_path.as_str());
+    }
+    for folder in folders {
+        remove_empty_dirs(Path::new(folder));
+    }
+}
 
-        sqlx::query(
-            r#"
-            UPDATE jobs
-            SET status = ?, error_message = NULL, progress_bytes = ?, eta_seconds = 0, speed_bytes_per_sec = 0, updated_at = ?
-            WHERE id = ?
-            "#,
+/// Moves the files listed on a prefetch job and records where they now live.
+async fn execute_file_transfer(job: JobRow, db: &DbPool, config: &Config) -> Result<(), JobError> {
+    let source_dir = PathBuf::from(&job.source_path);
+    let destination_dir = PathBuf::from(&job.destination_path);
+    let files: Vec<String> =
+        serde_json::from_str(job.files.as_deref().unwrap_or("[]")).map_err(io::Error::from)?;
+    let total_bytes = job.total_bytes.unwrap_or(0).max(0);
+
+    info!(
+        job_id = job.id,
+        show_id = job.show_id,
+        kind = %job.kind,
+        files = files.len(),
+        source = %source_dir.display(),
+        destination = %destination_dir.display(),
+        attempt = job.attempt,
+        "Starting prefetch job"
+    );
+
+    let mut moved: Vec<(&str, i64)> = Vec::new();
+    let mut copied_this_run: i64 = 0;
+    let start = Instant::now();
+
+    for relative in &files {
+        let source = source_dir.join(relative);
+        let target = destination_dir.join(relative);
+
+        // The copy finished before the job waited for playback.
+        if job.copied_at.is_some() {
+            let metadata = fs::metadata(&target).await?;
+            moved.push((relative, metadata.len().min(i64::MAX as u64) as i64));
+            continue;
+        }
+
+        let size = match fs::metadata(&source).await {
+            Ok(metadata) => metadata.len().min(i64::MAX as u64) as i64,
+            // Already moved by an earlier attempt that failed before finishing.
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let metadata = fs::metadata(&target).await?;
+                moved.push((relative, metadata.len().min(i64::MAX as u64) as i64));
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::copy(&source, &target).await?;
+        moved.push((relative, size));
+
+        copied_this_run = copied_this_run.saturating_add(size);
+        let elapsed = start.elapsed().as_secs_f64();
+        let speed = if elapsed > 0.0 {
+            (copied_this_run as f64 / elapsed) as i64
+        } else {
+            0
+        };
+        let remaining = total_bytes.saturating_sub(copied_this_run);
+        let eta = if speed > 0 {
+            remaining / speed.max(1)
+        } else {
+            0
+        };
+        update_job_progress(
+            db,
+            job.id,
+            copied_this_run.min(total_bytes),
+            copied_this_run,
+            speed,
+            eta,
         )
-        .bind(STATUS_SUCCESS)
-        .bind(final_progress)
-        .bind(updated_at)
-        .bind(job.id)
-        .execute(&mut *tx)
         .await?;
+    }
 
-        tx.commit().await?;
-        Ok(())
+    match source_idle(&job, db, config).await {
+        Ok(true) => {}
+        Ok(false) => return Ok(()),
+        Err(err) => {
+            if matches!(err, JobError::ShowInUse) {
+                for (relative, _) in &moved {
+                    if source_dir.join(relative).exists() {
+                        let _ = fs::remove_file(destination_dir.join(relative)).await;
+                    }
+                }
+            }
+            return Err(err);
+        }
     }
-    .await;
 
-    if let Err(err) = transaction_result {
-        error!(
-            ?err,
-            job_id = job.id,
-            show_id = job.show_id,
-            source = %source_path.display(),
-            destination = %destination_path.display(),
-            "Failed to finalize move job transaction"
-        );
-        return Err(err);
+    let now = Utc::now().timestamp();
+    let mut tx = db.begin().await?;
+    for (relative, size) in &moved {
+        if job.kind == KIND_PREFETCH {
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO prefetched_files (show_id, relative_path, hot_path, size_bytes, created_at)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(job.show_id)
+            .bind(relative)
+            .bind(&job.destination_path)
+            .bind(size)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            sqlx::query("DELETE FROM prefetched_files WHERE show_id = ? AND relative_path = ?")
+                .bind(job.show_id)
+                .bind(relative)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+    sqlx::query(
+        r#"
+        UPDATE jobs
+        SET status = ?, error_message = NULL, progress_bytes = ?, eta_seconds = 0, speed_bytes_per_sec = 0, updated_at = ?, finished_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(STATUS_SUCCESS)
+    .bind(total_bytes)
+    .bind(now)
+    .bind(now)
+    .bind(job.id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    for (relative, _) in &moved {
+        let source = source_dir.join(relative);
+        match fs::remove_file(&source).await {
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => warn!(?err, source = %source.display(), "Failed to delete moved file"),
+        }
+    }
+    if job.kind == KIND_PREFETCH_RETURN {
+        remove_empty_dirs(&source_dir);
     }
 
-    if let Err(err) = fs::remove_dir_all(&source_path).await {
+    info!(
+        job_id = job.id,
+        show_id = job.show_id,
+        "Prefetch job completed"
+    );
+    Ok(())
+}
+
+/// Deletes `root` and any directories below it that are left empty.
+fn remove_empty_dirs(root: &Path) {
+    for entry in WalkDir::new(root)
+        .contents_first(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_dir())
+    {
+        // Fails harmlessly for directories that still have content.
+        let _ = std::fs::remove_dir(entry.path());
+    }
+}
+
+async fn discard_copy(destination_path: &Path) {
+    if let Err(err) = fs::remove_dir_all(destination_path).await {
         warn!(
             ?err,
-            job_id = job.id,
-            show_id = job.show_id,
-            source = %source_path.display(),
-            "Failed to delete source directory after move"
+            destination = %destination_path.display(),
+            "Failed to remove copy of skipped move"
         );
     }
+}
 
-    info!(job_id = job.id, show_id = job.show_id, "Move job completed");
-    Ok(())
+/// Checks playback once more before the source is switched over and deleted.
+/// Returns `false` when Jellyfin is still streaming the show: the job is parked
+/// as `waiting_for_playback` with its copy kept, and the worker re-claims it
+/// after `recheck_seconds`. In skip mode this fails with `JobError::ShowInUse`
+/// and the caller discards what it copied.
+async fn source_idle(job: &JobRow, db: &DbPool, config: &Config) -> Result<bool, JobError> {
+    if !playback::show_in_use(config, &job.source_path).await {
+        return Ok(true);
+    }
+    if config.playback_guard.mode == PlaybackGuardMode::Skip {
+        return Err(JobError::ShowInUse);
+    }
+
+    info!(
+        job_id = job.id,
+        show_id = job.show_id,
+        "Copy finished; deferring removal of source until playback ends"
+    );
+    let now = Utc::now().timestamp();
+    sqlx::query(
+        r#"
+        UPDATE jobs
+        SET status = ?, copied_at = COALESCE(copied_at, ?), updated_at = ?, speed_bytes_per_sec = 0, eta_seconds = 0
+        WHERE id = ?
+        "#,
+    )
+    .bind(STATUS_WAITING_FOR_PLAYBACK)
+    .bind(now)
+    .bind(now)
+    .bind(job.id)
+    .execute(db)
+    .await?;
+    Ok(false)
 }
 
 async fn update_job_progress(
     db: &DbPool,
     job_id: i64,
     progress: i64,
+    copied_this_run: i64,
     speed: i64,
     eta: i64,
 ) -> Result<(), sqlx::Error> {
@@ -543,11 +1529,12 @@ async fn update_job_progress(
     sqlx::query(
         r#"
         UPDATE jobs
-        SET progress_bytes = ?, speed_bytes_per_sec = ?, eta_seconds = ?, updated_at = ?
+        SET progress_bytes = ?, bytes_copied_this_run = ?, speed_bytes_per_sec = ?, eta_seconds = ?, updated_at = ?
         WHERE id = ?
         "#,
     )
     .bind(progress)
+    .bind(copied_this_run)
     .bind(speed)
     .bind(eta)
     .bind(now)
@@ -568,7 +1555,7 @@ async fn finalize_job_status(
     sqlx::query(
         r#"
         UPDATE jobs
-        SET status = ?, error_message = ?, progress_bytes = COALESCE(?, progress_bytes), updated_at = ?, eta_seconds = 0, speed_bytes_per_sec = 0
+        SET status = ?, error_message = ?, progress_bytes = COALESCE(?, progress_bytes), updated_at = ?, finished_at = ?, eta_seconds = 0, speed_bytes_per_sec = 0
         WHERE id = ?
         "#,
     )
@@ -576,52 +1563,290 @@ async fn finalize_job_status(
     .bind(error_message)
     .bind(progress_override)
     .bind(now)
+    .bind(now)
     .bind(job_id)
     .execute(db)
     .await?;
     Ok(())
 }
 
-fn normalize_target(target: &str) -> Option<&'static str> {
-    match target.trim().to_lowercase().as_str() {
-        "hot" => Some("hot"),
-        "cold" => Some("cold"),
-        _ => None,
-    }
+/// The configured tier called `target`, ignoring case.
+pub fn normalize_target<'a>(config: &'a Config, target: &str) -> Option<&'a TierConfig> {
+    let target = target.trim();
+    config
+        .tiers
+        .iter()
+        .find(|tier| tier.name.eq_ignore_ascii_case(target))
 }
 
-fn trimmed_root(value: &str) -> Option<PathBuf> {
-    let trimmed = value.trim();
-    if trimmed.is_empty() {
-        None
-    } else {
-        Some(PathBuf::from(trimmed))
-    }
+/// Root with a trailing separator so `/mnt/cold` does not also match `/mnt/cold2`.
+pub fn root_prefix(root: &str) -> String {
+    format!("{}/", root.trim().trim_end_matches('/'))
 }
 
-fn detect_location<'a>(
-    show_path: &Path,
-    hot_root: &'a Path,
-    cold_root: &'a Path,
-) -> Result<(&'a str, &'a Path), JobError> {
-    if show_path.starts_with(hot_root) {
-        Ok(("hot", hot_root))
-    } else if show_path.starts_with(cold_root) {
-        Ok(("cold", cold_root))
-    } else {
-        Err(JobError::PathMismatch)
+fn detect_location<'a>(config: &'a Config, path: &Path) -> Result<&'a TierConfig, JobError> {
+    config.tier_for_path(path).ok_or(JobError::PathMismatch)
+}
+
+/// Refuses moves that would push `tier` past its `TierLimits`. Tiers whose
+/// usage cannot be read are not limited.
+async fn check_tier_limits(
+    db: &DbPool,
+    tier: &TierConfig,
+    incoming_bytes: i64,
+) -> Result<(), JobError> {
+    let limits = &tier.limits;
+    if limits.max_used_percent.is_none() && limits.min_free_bytes.is_none() {
+        return Ok(());
     }
+    let Some(usage) = policy::projected_usage(db, tier).await? else {
+        return Ok(());
+    };
+
+    if usage.exceeds(limits, incoming_bytes) {
+        return Err(JobError::TierFull(tier.name.clone()));
+    }
+    Ok(())
 }
 
-fn resolve_location_from_path(path: &Path, config: &Config) -> Result<&'static str, JobError> {
-    let hot_root = trimmed_root(&config.hot_root).ok_or(JobError::MissingRoot("hot_root"))?;
-    let cold_root = trimmed_root(&config.cold_root).ok_or(JobError::MissingRoot("cold_root"))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::TierConfig, test_support::test_pool};
 
-    if path.starts_with(&hot_root) {
-        Ok("hot")
-    } else if path.starts_with(&cold_root) {
-        Ok("cold")
-    } else {
-        Err(JobError::PathMismatch)
+    fn two_tier_config() -> Config {
+        let tier = |name: &str, root: &str| TierConfig {
+            name: name.to_string(),
+            roots: vec![root.to_string()],
+            ..TierConfig::default()
+        };
+        Config {
+            tiers: vec![tier("hot", "/media/hot"), tier("cold", "/media/cold")],
+            ..Config::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn guards_duplicate_jobs_and_replays_idempotency_keys() {
+        let (_dir, db) = test_pool().await;
+        let config = two_tier_config();
+        sqlx::query(
+            "INSERT INTO shows (id, title, path, location) VALUES \
+             (1, 'One', '/media/cold/tv/One', 'cold'), (2, 'Two', '/media/cold/tv/Two', 'cold')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let job = create_move_job(&db, &config, 1, "hot", Some("key-1"), false)
+            .await
+            .unwrap();
+        assert_eq!(job.destination_path, "/media/hot/tv/One");
+
+        let replayed = create_move_job(&db, &config, 1, "hot", Some("key-1"), false)
+            .await
+            .unwrap();
+        assert_eq!(replayed.id, job.id);
+
+        let duplicate = create_move_job(&db, &config, 1, "hot", None, false).await;
+        assert!(matches!(duplicate, Err(JobError::JobAlreadyActive(id)) if id == job.id));
+
+        let mismatch = create_move_job(&db, &config, 2, "hot", Some("key-1"), false).await;
+        assert!(matches!(mismatch, Err(JobError::IdempotencyKeyMismatch)));
+        let mismatch = create_move_job(&db, &config, 1, "cold", Some("key-1"), false).await;
+        assert!(matches!(mismatch, Err(JobError::IdempotencyKeyMismatch)));
+
+        let jobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(jobs, 1);
+    }
+
+    #[tokio::test]
+    async fn prefetch_jobs_respect_pins_and_quotas() {
+        let (_dir, db) = test_pool().await;
+        let config = Config {
+            library_quotas: vec![crate::config::LibraryQuota {
+                library: "/media/hot/tv".to_string(),
+                tier: None,
+                max_bytes: Some(100),
+                max_percent: None,
+            }],
+            ..two_tier_config()
+        };
+        sqlx::query(
+            "INSERT INTO shows (id, title, path, location, pinned_location) VALUES \
+             (1, 'One', '/media/cold/tv/One', 'cold', 'cold'), \
+             (2, 'Two', '/media/cold/tv/Two', 'cold', NULL)",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO prefetched_files (show_id, relative_path, hot_path, size_bytes, created_at) \
+             VALUES (2, 'S01E01.mkv', '/media/hot/tv/Two', 60, 0)",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let prefetch = |show_id: i64, size: i64| {
+            let cold = PathBuf::from(format!("/media/cold/tv/{show_id}"));
+            let hot = PathBuf::from(format!("/media/hot/tv/{show_id}"));
+            let db = db.clone();
+            let config = config.clone();
+            async move {
+                create_prefetch_job(
+                    &db,
+                    &config,
+                    show_id,
+                    KIND_PREFETCH,
+                    TransferEnd {
+                        tier: "cold",
+                        dir: &cold,
+                    },
+                    TransferEnd {
+                        tier: "hot",
+                        dir: &hot,
+                    },
+                    &["S01E02.mkv".to_string()],
+                    size,
+                )
+                .await
+            }
+        };
+
+        let pinned = prefetch(1, 10).await;
+        assert!(matches!(pinned, Err(JobError::ShowPinned(tier)) if tier == "cold"));
+        // The prefetched episode already takes 60 of the 100 byte quota.
+        let over = prefetch(2, 41).await;
+        assert!(matches!(over, Err(JobError::QuotaExceeded(_))));
+        assert!(prefetch(2, 40).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn moves_to_a_slower_tier_take_prefetched_episodes_along() {
+        let (dir, db) = test_pool().await;
+        let root = |tier: &str| dir.path().join(tier).to_string_lossy().into_owned();
+        let tier = |name: &str| TierConfig {
+            name: name.to_string(),
+            roots: vec![root(name)],
+            ..TierConfig::default()
+        };
+        let config = Config {
+            tiers: vec![tier("hot"), tier("warm"), tier("cold")],
+            ..Config::default()
+        };
+        let cold_show = dir.path().join("cold/tv/One");
+        let hot_show = dir.path().join("hot/tv/One");
+        std::fs::create_dir_all(&cold_show).unwrap();
+        std::fs::create_dir_all(&hot_show).unwrap();
+        std::fs::create_dir_all(dir.path().join("warm")).unwrap();
+        std::fs::write(cold_show.join("S01E01.mkv"), "one").unwrap();
+        std::fs::write(hot_show.join("S01E02.mkv"), "two").unwrap();
+        sqlx::query("INSERT INTO shows (id, title, path, location) VALUES (1, 'One', ?, 'cold')")
+            .bind(cold_show.to_string_lossy().as_ref())
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO prefetched_files (show_id, relative_path, hot_path, size_bytes, created_at) \
+             VALUES (1, 'S01E02.mkv', ?, 3, 0)",
+        )
+        .bind(hot_show.to_string_lossy().as_ref())
+        .execute(&db)
+        .await
+        .unwrap();
+
+        create_move_job(&db, &config, 1, "warm", None, false)
+            .await
+            .unwrap();
+        let job = fetch_next_job(&db, 0).await.unwrap().unwrap();
+        execute_job(job, &db, &config).await.unwrap();
+
+        let warm_show = dir.path().join("warm/tv/One");
+        assert!(warm_show.join("S01E01.mkv").exists());
+        assert!(warm_show.join("S01E02.mkv").exists());
+        assert!(!cold_show.exists());
+        assert!(!hot_show.exists());
+        let prefetched: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM prefetched_files")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(prefetched, 0);
+        let location: String = sqlx::query_scalar("SELECT location FROM shows WHERE id = 1")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(location, "warm");
+    }
+
+    #[tokio::test]
+    async fn refuses_moves_of_shows_split_across_branches() {
+        let (_dir, db) = test_pool().await;
+        let mut config = two_tier_config();
+        config.mergerfs.enabled = true;
+        config.mergerfs.union_root = "/media/union".to_string();
+        config.mergerfs.branches = vec!["/media/hot".to_string(), "/media/cold".to_string()];
+        sqlx::query(
+            "INSERT INTO shows (id, title, path, location) VALUES \
+             (1, 'One', '/media/cold/tv/One', 'cold'), (2, 'One', '/media/hot/tv/One', 'hot'), \
+             (3, 'Two', '/media/cold/tv/Two', 'cold')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let split = create_move_job(&db, &config, 1, "hot", None, false).await;
+        assert!(matches!(split, Err(JobError::ShowSplit(other)) if other == "/media/hot/tv/One"));
+        assert!(
+            create_move_job(&db, &config, 3, "hot", None, false)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn reclaiming_a_parked_job_keeps_its_attempt() {
+        let (_dir, db) = test_pool().await;
+        let config = two_tier_config();
+        sqlx::query(
+            "INSERT INTO shows (id, title, path, location) VALUES (1, 'One', '/media/cold/tv/One', 'cold')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        create_move_job(&db, &config, 1, "hot", None, false)
+            .await
+            .unwrap();
+
+        // Parked before it ever ran: the first real claim starts attempt 1.
+        let job = fetch_next_job(&db, 0).await.unwrap().unwrap();
+        mark_waiting_for_playback(&db, job.id).await.unwrap();
+        let job = fetch_next_job(&db, 0).await.unwrap().unwrap();
+        let claimed = claim_job(&db, job).await.unwrap();
+        assert_eq!(claimed.attempt, 1);
+        assert!(claimed.started_at.is_some());
+
+        // Parked after its copy: picking it up again continues that attempt.
+        sqlx::query("UPDATE jobs SET status = ?, copied_at = 1, started_at = 1 WHERE id = ?")
+            .bind(STATUS_WAITING_FOR_PLAYBACK)
+            .bind(claimed.id)
+            .execute(&db)
+            .await
+            .unwrap();
+        let job = fetch_next_job(&db, 0).await.unwrap().unwrap();
+        let reclaimed = claim_job(&db, job).await.unwrap();
+        assert_eq!(reclaimed.attempt, 1);
+        assert_eq!(reclaimed.started_at, Some(1));
+        let (status, attempt, stored_started_at): (String, i64, i64) =
+            sqlx::query_as("SELECT status, attempt, started_at FROM jobs WHERE id = ?")
+                .bind(claimed.id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!((status.as_str(), attempt), (STATUS_RUNNING, 1));
+        assert_eq!(stored_started_at, 1);
     }
 }
@@ -1,15 +1,33 @@
+mod activity;
 mod config;
 mod db;
+mod episodes;
+mod eviction;
+mod farm;
 mod jellyfin;
+mod job_analytics;
+mod job_retention;
 mod jobs;
+mod mergerfs;
 mod metrics_collector;
+mod nfo;
 mod paths;
+mod playback;
+mod policy;
 mod pools;
+mod prefetch;
+mod quotas;
+mod scan_runs;
 mod scanner;
 mod system;
+#[cfg(test)]
+mod test_support;
+mod tiers;
 mod user_settings;
+mod watcher;
 
 use std::{
+    collections::BTreeMap,
     env,
     net::{IpAddr, SocketAddr},
     path::{Path as StdPath, PathBuf},
@@ -21,13 +39,13 @@ use axum::{
     Json, Router,
     body::Body,
     extract::{Path, Query, State},
-    http::{Method, Request, StatusCode, header, HeaderValue},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode, header},
     response::{Html, IntoResponse},
-    routing::{get, patch, post},
+    routing::{delete, get, post, put},
 };
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, QueryBuilder, Sqlite, types::Json as SqlJson};
 use tokio::{
     signal,
     sync::{Mutex, RwLock, watch},
@@ -40,12 +58,13 @@ use tower_http::{
 use tracing::{error, info, warn};
 
 use crate::{
-    config::{Config, ConfigStore},
+    config::{Config, ConfigStore, MediaKind},
     db::DbPool,
     jellyfin::{JellyfinClient, JellyfinError, JellyfinOperationResponse},
     jobs::JobRecord,
     paths::{DirectoryEntry, PathsError},
     pools::PoolsResponse,
+    scanner::{ScanState, ScanStatus},
 };
 
 const LOCAL_CONFIG_PATH: &str = "config/app-config.json";
@@ -58,8 +77,12 @@ const JM_DB_PATH_ENV: &str = "JM_DB_PATH";
 const JM_LOG_LEVEL_ENV: &str = "JM_LOG_LEVEL";
 const JM_HOT_ROOT_ENV: &str = "JM_HOT_ROOT";
 const JM_COLD_ROOT_ENV: &str = "JM_COLD_ROOT";
+/// Longest substring accepted by the `error` filter of `GET /api/jobs`.
+const MAX_ERROR_FILTER_LEN: usize = 200;
 const DOCKER_FLAG_ENV: &str = "JELLYMOVER_IN_DOCKER";
 const ALT_DOCKER_FLAG_ENV: &str = "RUNNING_IN_DOCKER";
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
 const STATIC_DIR: &str = "/app/static";
 const STATIC_INDEX_FILE: &str = "index.html";
 const STATIC_PLACEHOLDER_HTML: &str = "<!DOCTYPE html><html><body><h1>Frontend bundle not found</h1>\
@@ -139,6 +162,15 @@ struct PathsQuery {
 #[derive(Deserialize)]
 struct MoveRequest {
     target: String,
+    /// Move the show even if it is pinned to another tier.
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Deserialize)]
+struct PinRequest {
+    /// A tier name, or `null` to remove the pin.
+    location: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -149,12 +181,81 @@ struct ShowsQuery {
     search: Option<String>,
     sort_by: Option<String>,
     sort_dir: Option<String>,
+    /// List only shows whose folder is gone; by default they are hidden.
+    #[serde(default)]
+    missing: bool,
+    genre: Option<String>,
+    tag: Option<String>,
+    studio: Option<String>,
+    /// NFO series status, e.g. `Ended`.
+    status: Option<String>,
+    year_from: Option<i64>,
+    year_to: Option<i64>,
+    min_rating: Option<f64>,
+    #[serde(alias = "type")]
+    kind: Option<MediaKind>,
 }
 
 #[derive(Deserialize)]
 struct JobsQuery {
     limit: Option<u32>,
     offset: Option<u32>,
+    /// Comma-separated list, e.g. `queued,running`.
+    status: Option<String>,
+    show_id: Option<i64>,
+    source_tier: Option<String>,
+    target_tier: Option<String>,
+    created_from: Option<i64>,
+    created_to: Option<i64>,
+    finished_from: Option<i64>,
+    finished_to: Option<i64>,
+    error: Option<String>,
+    strategy: Option<String>,
+    /// `move`, `prefetch` or `prefetch_return`.
+    kind: Option<String>,
+    sort_by: Option<String>,
+    sort_dir: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SimulatePoliciesRequest {
+    /// Candidate rules; the configured rules are simulated when omitted.
+    rules: Option<Vec<config::PolicyRule>>,
+}
+
+#[derive(Deserialize)]
+struct ScanQuery {
+    /// Rescan every show, even ones whose folders look unchanged.
+    #[serde(default)]
+    full: bool,
+    /// Delete shows flagged as missing once the scan is done.
+    #[serde(default)]
+    purge_missing: bool,
+}
+
+#[derive(Deserialize)]
+struct PruneJobsQuery {
+    /// Comma-separated terminal statuses; defaults to `success,failed`.
+    status: Option<String>,
+    /// Unix timestamp; only jobs finished before it are deleted.
+    before: Option<i64>,
+    /// Overrides whether pruned rows are archived when an archive dir is configured.
+    archive: Option<bool>,
+}
+
+/// Paginated list envelope shared by list endpoints.
+#[derive(Serialize)]
+struct Page<T> {
+    items: Vec<T>,
+    total: i64,
+    limit: i64,
+    offset: i64,
+}
+
+#[derive(Deserialize)]
+struct PageQuery {
+    limit: Option<u32>,
+    offset: Option<u32>,
 }
 
 #[derive(Deserialize)]
@@ -162,6 +263,12 @@ struct HistoryQuery {
     duration: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct JobAnalyticsQuery {
+    bucket: Option<String>,
+    duration: Option<String>,
+}
+
 #[derive(Serialize, FromRow)]
 struct SystemMetricsHistoryPoint {
     timestamp: i64,
@@ -174,7 +281,7 @@ struct SystemMetricsHistoryPoint {
 #[derive(Serialize, FromRow)]
 struct PoolUsageHistoryPoint {
     timestamp: i64,
-    pool_type: String,
+    tier: String,
     total_bytes: i64,
     used_bytes: i64,
     free_bytes: i64,
@@ -185,53 +292,80 @@ struct JobAnalytics {
     total_jobs: i64,
     running_count: i64,
     queued_count: i64,
+    waiting_for_playback_count: i64,
     completed_count: i64,
     failed_count: i64,
     total_bytes_moved: i64,
+    bucket_seconds: i64,
+    buckets: Vec<job_analytics::JobAnalyticsBucket>,
 }
 
 #[derive(Serialize, FromRow)]
 struct ShowRecord {
     id: i64,
     title: Option<String>,
+    /// `series`, `movie` or `other`.
+    kind: String,
     path: String,
     location: Option<String>,
     size_bytes: Option<i64>,
     season_count: Option<i64>,
     episode_count: Option<i64>,
     thumbnail_path: Option<String>,
+    pinned_location: Option<String>,
+    last_played_at: Option<i64>,
+    play_count: i64,
+    active_viewers: i64,
+    /// When the show's folder was found to be gone.
+    missing_since: Option<i64>,
+    original_title: Option<String>,
+    year: Option<i64>,
+    premiered: Option<String>,
+    end_date: Option<String>,
+    rating: Option<f64>,
+    series_status: Option<String>,
+    genres: SqlJson<Vec<String>>,
+    tags: SqlJson<Vec<String>>,
+    studios: SqlJson<Vec<String>>,
+    /// Provider name to id, e.g. `tvdb`.
+    provider_ids: SqlJson<BTreeMap<String, String>>,
+    /// Where Jellyfin sees the show in mergerfs mode.
+    #[sqlx(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    union_path: Option<String>,
 }
 
-#[derive(Clone, Serialize)]
-enum ScanState {
-    Idle,
-    Running,
-}
-
-#[derive(Clone, Serialize)]
-struct ScanStatus {
-    state: ScanState,
-    last_started: Option<i64>,
-    last_finished: Option<i64>,
-    last_error: Option<String>,
-}
-
-impl Default for ScanStatus {
-    fn default() -> Self {
-        Self {
-            state: ScanState::Idle,
-            last_started: None,
-            last_finished: None,
-            last_error: None,
-        }
+impl ShowRecord {
+    fn with_union_path(mut self, config: &Config) -> Self {
+        self.union_path = mergerfs::union_path_for(config, StdPath::new(&self.path))
+            .map(|path| path.display().to_string());
+        self
     }
 }
 
+/// Column list and join backing `ShowRecord`; append `WHERE`/`ORDER BY` as needed.
+const SHOW_SELECT: &str = "SELECT shows.id, title, kind, path, location, size_bytes, season_count, \
+     episode_count, thumbnail_path, pinned_location, show_activity.last_played_at, \
+     COALESCE(show_activity.play_count, 0) AS play_count, \
+     COALESCE(show_activity.active_viewers, 0) AS active_viewers, missing_since, \
+     original_title, year, premiered, end_date, rating, series_status, \
+     (SELECT json_group_array(name) FROM (SELECT g.name FROM show_genres sg \
+       JOIN genres g ON g.id = sg.genre_id WHERE sg.show_id = shows.id ORDER BY sg.rowid)) AS genres, \
+     (SELECT json_group_array(name) FROM (SELECT t.name FROM show_tags st \
+       JOIN tags t ON t.id = st.tag_id WHERE st.show_id = shows.id ORDER BY st.rowid)) AS tags, \
+     COALESCE(studios, '[]') AS studios, COALESCE(provider_ids, '{}') AS provider_ids \
+     FROM shows LEFT JOIN show_activity ON show_activity.show_id = shows.id";
+
 #[derive(Serialize)]
 struct ScanTriggerResponse {
     status: &'static str,
 }
 
+#[derive(Serialize)]
+struct PurgeMissingResponse {
+    purged: u64,
+}
+
 /// Validates if an origin is allowed for CORS.
 /// For TrueNAS SCALE deployments, allows:
 /// - localhost/127.0.0.1 (development)
@@ -316,24 +450,19 @@ async fn main() {
     };
 
     if !config_existed {
-        let mut seeded_config = initial_config.clone();
-        let mut updated = false;
-        if let Some(hot) = settings.seed_hot_root.as_deref() {
-            seeded_config.hot_root = hot.to_string();
-            updated = true;
-        }
-        if let Some(cold) = settings.seed_cold_root.as_deref() {
-            seeded_config.cold_root = cold.to_string();
-            updated = true;
+        initial_config.legacy_hot_root = settings.seed_hot_root.clone();
+        initial_config.legacy_cold_root = settings.seed_cold_root.clone();
+    }
+    let created_tiers = mergerfs::detect_branches(&mut initial_config);
+    if initial_config.migrate_legacy_roots() || created_tiers {
+        if let Err(err) = config_store.save(&initial_config) {
+            error!(?err, "Failed to save configuration with storage tiers");
+            std::process::exit(1);
         }
-
-        if updated {
-            if let Err(err) = config_store.save(&seeded_config) {
-                error!(?err, "Failed to seed configuration from environment");
-                std::process::exit(1);
-            }
+        if config_existed {
+            info!("Migrated hot_root/cold_root to storage tiers");
+        } else {
             info!("Configuration seeded from environment defaults");
-            initial_config = seeded_config;
         }
     }
 
@@ -349,6 +478,10 @@ async fn main() {
         }
     };
 
+    if let Err(err) = tiers::relabel_rows(&db_pool, &initial_config).await {
+        error!(?err, "Failed to relabel rows for configured tiers");
+    }
+
     let state = AppState {
         config: Arc::new(RwLock::new(initial_config)),
         store: config_store,
@@ -368,6 +501,29 @@ async fn main() {
         state.system_monitor.clone(),
     );
 
+    let _retention_handle = job_retention::start_pruner(state.db.clone(), state.config.clone());
+    let _activity_handle = activity::start_sync(state.db.clone(), state.config.clone());
+    let _policy_handle = policy::start_engine(
+        state.db.clone(),
+        state.config.clone(),
+        state.scan_status.clone(),
+    );
+    let _eviction_handle = eviction::start_evictor(
+        state.db.clone(),
+        state.config.clone(),
+        state.scan_status.clone(),
+    );
+    let _prefetch_handle = prefetch::start_prefetcher(
+        state.db.clone(),
+        state.config.clone(),
+        state.scan_status.clone(),
+    );
+    let _watcher_handle = watcher::start_watcher(
+        state.db.clone(),
+        state.config.clone(),
+        state.scan_status.clone(),
+    );
+
     // CORS configuration for TrueNAS SCALE deployments
     // Allows private IPs and localhost, rejects public origins
     let cors = CorsLayer::new()
@@ -381,7 +537,11 @@ async fn main() {
             Method::DELETE,
             Method::OPTIONS,
         ])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+        ]);
 
     let app = Router::new()
         .route("/health", get(health))
@@ -394,15 +554,34 @@ async fn main() {
         .route("/api/stats/history", get(get_system_stats_history))
         .route("/api/scan", post(trigger_scan))
         .route("/api/scan/status", get(get_scan_status))
+        .route("/api/scan/cancel", post(cancel_scan))
+        .route("/api/scan/runs", get(list_scan_runs))
         .route("/api/jellyfin/rescan", post(trigger_jellyfin_rescan))
         .route("/api/jellyfin/scan/status", get(get_jellyfin_scan_status))
         .route("/api/jellyfin/status", get(get_jellyfin_status))
+        .route("/api/jellyfin/activity/sync", post(sync_jellyfin_activity))
         .route("/api/shows", get(list_shows))
+        .route("/api/shows/missing", delete(purge_missing_shows))
+        .route("/api/shows/:id/episodes", get(list_show_episodes))
         .route("/api/shows/:id/thumbnail", get(get_show_thumbnail))
         .route("/api/shows/:id/move", post(create_move_job_handler))
-        .route("/api/jobs", get(list_jobs_handler))
+        .route("/api/shows/:id/pin", put(set_show_pin))
+        .route(
+            "/api/jobs",
+            get(list_jobs_handler).delete(prune_jobs_handler),
+        )
         .route("/api/jobs/:id", get(get_job_handler))
         .route("/api/jobs/analytics", get(get_job_analytics))
+        .route("/api/policies/run", post(run_policies_handler))
+        .route("/api/policies/simulate", post(simulate_policies_handler))
+        .route(
+            "/api/policies/decisions",
+            get(list_policy_decisions_handler),
+        )
+        .route("/api/prefetch/run", post(run_prefetch_handler))
+        .route("/api/farm", get(check_farm_handler))
+        .route("/api/mergerfs", get(get_mergerfs_status))
+        .route("/api/farm/reconcile", post(reconcile_farm_handler))
         .with_state(state)
         .layer(cors)
         .fallback(static_fallback);
@@ -461,8 +640,10 @@ async fn get_config(State(state): State<AppState>) -> Json<Config> {
 
 async fn update_config(
     State(state): State<AppState>,
-    Json(payload): Json<Config>,
+    Json(mut payload): Json<Config>,
 ) -> Result<Json<Config>, (StatusCode, Json<ErrorResponse>)> {
+    payload.migrate_legacy_roots();
+    mergerfs::detect_branches(&mut payload);
     if let Err(err) = config::validate_config(&payload) {
         return Err(error_response_with_details(
             StatusCode::BAD_REQUEST,
@@ -484,6 +665,10 @@ async fn update_config(
         *config_guard = payload.clone();
     }
 
+    if let Err(err) = tiers::relabel_rows(&state.db, &payload).await {
+        error!(?err, "Failed to relabel rows for configured tiers");
+    }
+
     Ok(Json(payload))
 }
 
@@ -509,9 +694,15 @@ async fn list_paths(
 
 async fn get_pools(State(state): State<AppState>) -> Json<PoolsResponse> {
     let config = state.config.read().await.clone();
-    let hot = pools::collect_pool_usage(&config.hot_root);
-    let cold = pools::collect_pool_usage(&config.cold_root);
-    Json(PoolsResponse { hot, cold })
+    let tiers = pools::tier_summaries(&config.tiers).await;
+    let quotas = match quotas::QuotaTracker::load(&state.db, &config).await {
+        Ok(tracker) => tracker.usage(),
+        Err(err) => {
+            error!(?err, "Failed to load library quota usage");
+            Vec::new()
+        }
+    };
+    Json(PoolsResponse { tiers, quotas })
 }
 
 async fn get_system_stats(State(state): State<AppState>) -> Json<system::SystemStats> {
@@ -547,6 +738,34 @@ async fn trigger_jellyfin_rescan(
     }
 }
 
+async fn sync_jellyfin_activity(
+    State(state): State<AppState>,
+) -> Result<Json<activity::ActivitySyncSummary>, (StatusCode, Json<ErrorResponse>)> {
+    let config = state.config.read().await.clone();
+
+    match activity::sync_once(&state.db, &config).await {
+        Ok(summary) => Ok(Json(summary)),
+        Err(activity::ActivityError::Jellyfin(JellyfinError::NotConfigured)) => Err(
+            error_response(StatusCode::BAD_REQUEST, "Jellyfin not configured"),
+        ),
+        Err(activity::ActivityError::Jellyfin(JellyfinError::UnexpectedStatus(status)))
+            if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN =>
+        {
+            Err(error_response(
+                StatusCode::UNAUTHORIZED,
+                "Invalid Jellyfin API key",
+            ))
+        }
+        Err(error) => {
+            error!(%error, "Failed to sync Jellyfin activity");
+            Err(error_response(
+                StatusCode::BAD_GATEWAY,
+                "Failed to sync Jellyfin activity",
+            ))
+        }
+    }
+}
+
 async fn get_jellyfin_status(
     State(state): State<AppState>,
 ) -> Result<Json<crate::jellyfin::JellyfinStatus>, (StatusCode, Json<ErrorResponse>)> {
@@ -616,6 +835,7 @@ async fn get_jellyfin_scan_status(
             last_started,
             last_finished,
             last_error,
+            ..ScanStatus::default()
         }))
     } else {
         // Task not found, return default idle status
@@ -625,6 +845,7 @@ async fn get_jellyfin_scan_status(
 
 async fn trigger_scan(
     State(state): State<AppState>,
+    Query(query): Query<ScanQuery>,
 ) -> Result<Json<ScanTriggerResponse>, (StatusCode, Json<ErrorResponse>)> {
     let config_snapshot = state.config.read().await.clone();
     if !config::config_is_ready(&config_snapshot) {
@@ -653,27 +874,61 @@ async fn trigger_scan(
 
     {
         let status = state.scan_status.read().await;
-        if matches!(status.state, ScanState::Running) {
+        if status.is_running() {
             return Err(error_response(StatusCode::CONFLICT, "scan_already_running"));
         }
     }
 
+    let started_at = Utc::now().timestamp();
     {
         let mut status = state.scan_status.write().await;
         status.state = ScanState::Running;
-        status.last_started = Some(Utc::now().timestamp());
+        status.last_started = Some(started_at);
         status.last_error = None;
+        status.progress = Some(scanner::ScanProgress::default());
+        status.cancel_requested.set(false);
     }
 
     let scan_status = state.scan_status.clone();
     let db = state.db.clone();
 
     tokio::spawn(async move {
-        let result = scanner::run_scan(config_snapshot, db).await;
+        let options = scanner::ScanOptions {
+            full: query.full,
+            purge_missing: query.purge_missing,
+        };
+        let result =
+            scanner::run_scan(config_snapshot.clone(), db.clone(), options, &scan_status).await;
+        let finished_at = Utc::now().timestamp();
+        if let Err(err) = scan_runs::record(&db, started_at, finished_at, query.full, &result).await
+        {
+            error!(?err, "Failed to record scan run");
+        }
+        // New shows get their stable path as soon as they are discovered; a
+        // cancelled scan leaves the farm alone.
+        if result.as_ref().is_ok_and(|summary| !summary.cancelled) {
+            match farm::reconcile(&db, &config_snapshot, false).await {
+                Ok(Some(report)) if !report.conflicts.is_empty() => warn!(
+                    conflicts = report.conflicts.len(),
+                    "Stable path farm has links that could not be put in place"
+                ),
+                Ok(_) => {}
+                Err(err) => error!(?err, "Failed to reconcile stable path farm"),
+            }
+        }
         let mut status = scan_status.write().await;
         status.state = ScanState::Idle;
-        status.last_finished = Some(Utc::now().timestamp());
+        status.last_finished = Some(finished_at);
+        status.progress = None;
+        status.cancel_requested.set(false);
         match result {
+            Ok(summary) if summary.cancelled => {
+                status.last_error = None;
+                info!(
+                    processed = summary.shows_processed,
+                    "Filesystem scan cancelled on request"
+                );
+            }
             Ok(summary) => {
                 status.last_error = None;
                 info!(
@@ -681,6 +936,9 @@ async fn trigger_scan(
                     processed = summary.shows_processed,
                     inserted = summary.inserted,
                     updated = summary.updated,
+                    unchanged = summary.unchanged,
+                    removed = summary.removed,
+                    purged = summary.purged,
                     "Filesystem scan finished"
                 );
             }
@@ -695,10 +953,49 @@ async fn trigger_scan(
 }
 
 async fn get_scan_status(State(state): State<AppState>) -> Json<ScanStatus> {
-    let status = state.scan_status.read().await.clone();
+    let mut status = state.scan_status.read().await.clone();
+    if let (Some(progress), Some(started)) = (status.progress.as_mut(), status.last_started) {
+        progress.elapsed_seconds = Utc::now().timestamp() - started;
+    }
     Json(status)
 }
 
+async fn cancel_scan(
+    State(state): State<AppState>,
+) -> Result<Json<ScanStatus>, (StatusCode, Json<ErrorResponse>)> {
+    let status = state.scan_status.read().await;
+    if !status.is_running() {
+        return Err(error_response(StatusCode::CONFLICT, "scan_not_running"));
+    }
+    status.cancel_requested.set(true);
+    info!("Filesystem scan cancellation requested");
+    Ok(Json(status.clone()))
+}
+
+async fn list_scan_runs(
+    State(state): State<AppState>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<Page<scan_runs::ScanRun>>, (StatusCode, Json<ErrorResponse>)> {
+    let limit = query.limit.unwrap_or(50).min(500) as i64;
+    let offset = query.offset.unwrap_or(0) as i64;
+
+    match scan_runs::list(&state.db, limit, offset).await {
+        Ok((items, total)) => Ok(Json(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })),
+        Err(err) => {
+            error!(?err, "Failed to list scan runs");
+            Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to list scan runs",
+            ))
+        }
+    }
+}
+
 fn running_in_docker() -> bool {
     env_bool(DOCKER_FLAG_ENV)
         .or_else(|| env_bool(ALT_DOCKER_FLAG_ENV))
@@ -763,10 +1060,89 @@ async fn static_fallback(req: Request<Body>) -> impl IntoResponse {
     }
 }
 
+/// Filters accepted by `GET /api/shows`.
+struct ShowFilter {
+    missing: bool,
+    location: Option<String>,
+    search_pattern: Option<String>,
+    genre: Option<String>,
+    tag: Option<String>,
+    studio: Option<String>,
+    status: Option<String>,
+    year_from: Option<i64>,
+    year_to: Option<i64>,
+    min_rating: Option<f64>,
+    kind: Option<MediaKind>,
+}
+
+fn push_show_filter<'a>(query: &mut QueryBuilder<'a, Sqlite>, filter: &'a ShowFilter) {
+    query.push(if filter.missing {
+        " WHERE missing_since IS NOT NULL"
+    } else {
+        " WHERE missing_since IS NULL"
+    });
+    if let Some(location) = &filter.location {
+        query
+            .push(" AND lower(location) = lower(")
+            .push_bind(location)
+            .push(")");
+    }
+    if let Some(pattern) = &filter.search_pattern {
+        query
+            .push(" AND (title LIKE ")
+            .push_bind(pattern)
+            .push(" OR path LIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+    if let Some(genre) = &filter.genre {
+        query
+            .push(
+                " AND EXISTS (SELECT 1 FROM show_genres sg JOIN genres g ON g.id = sg.genre_id \
+                 WHERE sg.show_id = shows.id AND g.name = ",
+            )
+            .push_bind(genre)
+            .push(")");
+    }
+    if let Some(tag) = &filter.tag {
+        query
+            .push(
+                " AND EXISTS (SELECT 1 FROM show_tags st JOIN tags t ON t.id = st.tag_id \
+                 WHERE st.show_id = shows.id AND t.name = ",
+            )
+            .push_bind(tag)
+            .push(")");
+    }
+    if let Some(studio) = &filter.studio {
+        query
+            .push(" AND EXISTS (SELECT 1 FROM json_each(shows.studios) WHERE lower(value) = lower(")
+            .push_bind(studio)
+            .push("))");
+    }
+    if let Some(status) = &filter.status {
+        query
+            .push(" AND lower(series_status) = lower(")
+            .push_bind(status)
+            .push(")");
+    }
+    if let Some(year_from) = filter.year_from {
+        query.push(" AND year >= ").push_bind(year_from);
+    }
+    if let Some(year_to) = filter.year_to {
+        query.push(" AND year <= ").push_bind(year_to);
+    }
+    if let Some(min_rating) = filter.min_rating {
+        query.push(" AND rating >= ").push_bind(min_rating);
+    }
+    if let Some(kind) = filter.kind {
+        query.push(" AND kind = ").push_bind(kind.as_str());
+    }
+}
+
 async fn list_shows(
     State(state): State<AppState>,
     Query(query): Query<ShowsQuery>,
-) -> Result<Json<Vec<ShowRecord>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Page<ShowRecord>>, (StatusCode, Json<ErrorResponse>)> {
     let location = query
         .location
         .as_deref()
@@ -791,6 +1167,11 @@ async fn list_shows(
         Some("date") => "last_scan",
         Some("seasons") => "season_count",
         Some("episodes") => "episode_count",
+        Some("last_played") => "show_activity.last_played_at",
+        Some("plays") => "play_count",
+        Some("viewers") => "active_viewers",
+        Some("year") => "year",
+        Some("rating") => "rating",
         _ => "title COLLATE NOCASE", // default
     };
 
@@ -800,60 +1181,157 @@ async fn list_shows(
         _ => "ASC", // default
     };
 
-    // Build dynamic SQL query
-    let mut sql = String::from(
-        "SELECT id, title, path, location, size_bytes, season_count, episode_count, thumbnail_path FROM shows"
-    );
-
-    let mut where_clauses = Vec::new();
-
-    // Add location filter if provided
-    if location.is_some() {
-        where_clauses.push("lower(location) = lower(?)".to_string());
-    }
+    let text_filter = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let filter = ShowFilter {
+        missing: query.missing,
+        location,
+        search_pattern: search.map(|term| format!("%{term}%")),
+        genre: text_filter(&query.genre),
+        tag: text_filter(&query.tag),
+        studio: text_filter(&query.studio),
+        status: text_filter(&query.status),
+        year_from: query.year_from,
+        year_to: query.year_to,
+        min_rating: query.min_rating,
+        kind: query.kind,
+    };
 
-    // Add search filter if provided
-    if search.is_some() {
-        where_clauses.push("(title LIKE ? OR path LIKE ?)".to_string());
+    // Count matches with the same filters so clients can paginate
+    let mut count_query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM shows");
+    push_show_filter(&mut count_query, &filter);
+
+    let mut db_query = QueryBuilder::<Sqlite>::new(SHOW_SELECT);
+    push_show_filter(&mut db_query, &filter);
+    db_query.push(format!(
+        " ORDER BY {sort_column} {sort_direction} NULLS LAST, shows.id {sort_direction}"
+    ));
+    db_query.push(" LIMIT ").push_bind(limit);
+    db_query.push(" OFFSET ").push_bind(offset);
+
+    let result = async {
+        let total: i64 = count_query
+            .build_query_scalar()
+            .fetch_one(&state.db)
+            .await?;
+        let rows = db_query
+            .build_query_as::<ShowRecord>()
+            .fetch_all(&state.db)
+            .await?;
+        Ok::<_, sqlx::Error>((rows, total))
     }
+    .await;
 
-    if !where_clauses.is_empty() {
-        sql.push_str(" WHERE ");
-        sql.push_str(&where_clauses.join(" AND "));
+    let config = state.config.read().await;
+    match result {
+        Ok((items, total)) => Ok(Json(Page {
+            items: items
+                .into_iter()
+                .map(|show| show.with_union_path(&config))
+                .collect(),
+            total,
+            limit,
+            offset,
+        })),
+        Err(err) => {
+            error!(?err, "Failed to fetch shows");
+            Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to fetch shows",
+            ))
+        }
     }
+}
 
-    // Add ORDER BY clause
-    sql.push_str(&format!(" ORDER BY {} {}", sort_column, sort_direction));
-
-    // Add LIMIT and OFFSET
-    sql.push_str(" LIMIT ? OFFSET ?");
+async fn purge_missing_shows(
+    State(state): State<AppState>,
+) -> Result<Json<PurgeMissingResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let archive_dir = job_retention::archive_dir(&*state.config.read().await);
+    match scanner::purge_missing(&state.db, archive_dir.as_deref()).await {
+        Ok(purged) => {
+            info!(purged, "Purged missing shows on request");
+            Ok(Json(PurgeMissingResponse { purged }))
+        }
+        Err(err) => {
+            error!(%err, "Failed to purge missing shows");
+            Err(error_response(
 
-    // Prepare search pattern outside the match to extend its lifetime
-    let search_pattern = search.as_ref().map(|term| format!("%{}%", term));
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to purge missing shows",
+            ))
+        }
+    }
+}
 
-    // Build and execute query with proper parameter binding
-    let mut db_query = sqlx::query_as::<_, ShowRecord>(&sql);
+async fn set_show_pin(
+    State(state): State<AppState>,
+    Path(show_id): Path<i64>,
+    Json(payload): Json<PinRequest>,
+) -> Result<Json<ShowRecord>, (StatusCode, Json<ErrorResponse>)> {
+    let pinned_location = match payload.location.as_deref() {
+        None => None,
+        Some(location) => {
+            let config = state.config.read().await;
+            let tier = jobs::normalize_target(&config, location).ok_or_else(|| {
+                error_response(
+                    StatusCode::BAD_REQUEST,
+                    "location must be a configured tier name or null",
+                )
+            })?;
+            Some(tier.name.clone())
+        }
+    };
 
-    if let Some(loc) = location {
-        db_query = db_query.bind(loc);
+    let result = async {
+        let updated = sqlx::query("UPDATE shows SET pinned_location = ? WHERE id = ?")
+            .bind(pinned_location)
+            .bind(show_id)
+            .execute(&state.db)
+            .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        sqlx::query_as::<_, ShowRecord>(&format!("{SHOW_SELECT} WHERE shows.id = ?"))
+            .bind(show_id)
+            .fetch_optional(&state.db)
+            .await
     }
+    .await;
 
-    if let Some(pattern) = &search_pattern {
-        db_query = db_query.bind(pattern);
-        db_query = db_query.bind(pattern);
+    match result {
+        Ok(Some(show)) => {
+            info!(show_id, pinned_location = ?show.pinned_location, "Updated show pin");
+            let config = state.config.read().await;
+            Ok(Json(show.with_union_path(&config)))
+        }
+        Ok(None) => Err(error_response(StatusCode::NOT_FOUND, "Show not found")),
+        Err(err) => {
+            error!(?err, show_id, "Failed to update show pin");
+            Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to update show pin",
+            ))
+        }
     }
+}
 
-    db_query = db_query.bind(limit).bind(offset);
-
-    let rows = db_query.fetch_all(&state.db).await;
-
-    match rows {
-        Ok(rows) => Ok(Json(rows)),
+async fn list_show_episodes(
+    State(state): State<AppState>,
+    Path(show_id): Path<i64>,
+) -> Result<Json<episodes::ShowEpisodes>, (StatusCode, Json<ErrorResponse>)> {
+    match episodes::list(&state.db, show_id).await {
+        Ok(Some(episodes)) => Ok(Json(episodes)),
+        Ok(None) => Err(error_response(StatusCode::NOT_FOUND, "Show not found")),
         Err(err) => {
-            error!(?err, "Failed to fetch shows");
+            error!(?err, show_id, "Failed to list episodes");
             Err(error_response(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to fetch shows",
+                "Database error",
             ))
         }
     }
@@ -884,8 +1362,7 @@ async fn get_show_thumbnail(
     let config = state.config.read().await;
     let path_buf = PathBuf::from(&thumbnail_path);
 
-    let is_allowed = path_buf.starts_with(&config.hot_root)
-        || path_buf.starts_with(&config.cold_root)
+    let is_allowed = config.tier_for_path(&path_buf).is_some()
         || config.library_paths.iter().any(|lib_path| path_buf.starts_with(lib_path));
 
     if !is_allowed {
@@ -931,11 +1408,30 @@ async fn get_show_thumbnail(
 async fn create_move_job_handler(
     State(state): State<AppState>,
     Path(show_id): Path<i64>,
+    headers: HeaderMap,
     Json(payload): Json<MoveRequest>,
 ) -> Result<Json<JobRecord>, (StatusCode, Json<ErrorResponse>)> {
+    let idempotency_key = match headers.get(IDEMPOTENCY_KEY_HEADER) {
+        None => None,
+        Some(value) => {
+            let key = value
+                .to_str()
+                .ok()
+                .map(str::trim)
+                .filter(|key| !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LEN)
+                .ok_or_else(|| {
+                    error_response(
+                        StatusCode::BAD_REQUEST,
+                        "Idempotency-Key must be 1-255 visible ASCII characters",
+                    )
+                })?;
+            Some(key.to_string())
+        }
+    };
+
     {
         let status = state.scan_status.read().await;
-        if matches!(status.state, ScanState::Running) {
+        if status.is_running() {
             return Err(error_response(
                 StatusCode::CONFLICT,
                 "Cannot create move jobs while a scan is running.",
@@ -951,7 +1447,16 @@ async fn create_move_job_handler(
         ));
     }
 
-    match jobs::create_move_job(&state.db, &config_snapshot, show_id, &payload.target).await {
+    match jobs::create_move_job(
+        &state.db,
+        &config_snapshot,
+        show_id,
+        &payload.target,
+        idempotency_key.as_deref(),
+        payload.force,
+    )
+    .await
+    {
         Ok(job) => Ok(Json(job)),
         Err(error) => Err(job_error_response(error)),
     }
@@ -960,12 +1465,82 @@ async fn create_move_job_handler(
 async fn list_jobs_handler(
     State(state): State<AppState>,
     Query(query): Query<JobsQuery>,
-) -> Result<Json<Vec<JobRecord>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Page<JobRecord>>, (StatusCode, Json<ErrorResponse>)> {
     let limit = query.limit.unwrap_or(50).min(500) as i64;
     let offset = query.offset.unwrap_or(0) as i64;
 
-    match jobs::list_jobs(&state.db, limit, offset).await {
-        Ok(records) => Ok(Json(records)),
+    let error_contains = query
+        .error
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string());
+    if error_contains
+        .as_ref()
+        .is_some_and(|value| value.chars().count() > MAX_ERROR_FILTER_LEN)
+    {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            format!("error filter must be at most {MAX_ERROR_FILTER_LEN} characters"),
+        ));
+    }
+    let sort = jobs::JobSort::parse(query.sort_by.as_deref()).ok_or_else(|| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            "sort_by must be one of created, updated, started, finished, duration, size, status, attempt",
+        )
+    })?;
+
+    let filter = jobs::JobFilter {
+        statuses: query
+            .status
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(|value| value.trim().to_lowercase())
+            .filter(|value| !value.is_empty())
+            .collect(),
+        show_id: query.show_id,
+        source_tier: query
+            .source_tier
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string()),
+        target_tier: query
+            .target_tier
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string()),
+        created_from: query.created_from,
+        created_to: query.created_to,
+        finished_from: query.finished_from,
+        finished_to: query.finished_to,
+        error_contains,
+        strategy: query
+            .strategy
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string()),
+        kind: query
+            .kind
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string()),
+    };
+    // Newest first unless the caller explicitly asks otherwise
+    let descending = !matches!(query.sort_dir.as_deref(), Some("asc") | Some("ASC"));
+
+    match jobs::list_jobs(&state.db, &filter, sort, descending, limit, offset).await {
+        Ok((items, total)) => Ok(Json(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })),
         Err(err) => {
             error!(?err, "Failed to list jobs");
             Err(error_response(
@@ -976,6 +1551,60 @@ async fn list_jobs_handler(
     }
 }
 
+async fn prune_jobs_handler(
+    State(state): State<AppState>,
+    Query(query): Query<PruneJobsQuery>,
+) -> Result<Json<job_retention::PruneSummary>, (StatusCode, Json<ErrorResponse>)> {
+    let before = query.before.ok_or_else(|| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            "before query parameter is required",
+        )
+    })?;
+
+    let mut statuses = Vec::new();
+    for value in query
+        .status
+        .as_deref()
+        .unwrap_or("success,failed")
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        match value.to_lowercase().as_str() {
+            "success" => statuses.push(jobs::STATUS_SUCCESS),
+            "failed" => statuses.push(jobs::STATUS_FAILED),
+            _ => {
+                return Err(error_response(
+                    StatusCode::BAD_REQUEST,
+                    "status must be 'success' and/or 'failed'",
+                ));
+            }
+        }
+    }
+
+    let archive_dir = if query.archive.unwrap_or(true) {
+        let config = state.config.read().await;
+        job_retention::archive_dir(&config)
+    } else {
+        None
+    };
+
+    match job_retention::prune_jobs(&state.db, &statuses, before, archive_dir.as_deref()).await {
+        Ok(summary) => {
+            info!(deleted = summary.deleted, before, "Pruned jobs on request");
+            Ok(Json(summary))
+        }
+        Err(err) => {
+            error!(%err, "Failed to prune jobs");
+            Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to prune jobs",
+            ))
+        }
+    }
+}
+
 async fn get_job_handler(
     State(state): State<AppState>,
     Path(job_id): Path<i64>,
@@ -993,12 +1622,174 @@ async fn get_job_handler(
     }
 }
 
+async fn simulate_policies_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<SimulatePoliciesRequest>,
+) -> Result<Json<policy::PolicySimulation>, (StatusCode, Json<ErrorResponse>)> {
+    let config_snapshot = state.config.read().await.clone();
+    if !config::config_is_ready(&config_snapshot) {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Configuration incomplete. Please finish setup before simulating policies.",
+        ));
+    }
+
+    let candidate = config::PolicyConfig {
+        rules: payload
+            .rules
+            .unwrap_or_else(|| config_snapshot.policies.rules.clone()),
+        ..config_snapshot.policies.clone()
+    };
+    if let Err(err) = config::validate_policies(&candidate, &config_snapshot.tiers) {
+        return Err(error_response_with_details(
+            StatusCode::BAD_REQUEST,
+            "Validation failed",
+            err.to_string(),
+        ));
+    }
+
+    match policy::simulate_rules(&state.db, &config_snapshot, &candidate.rules).await {
+        Ok(simulation) => Ok(Json(simulation)),
+        Err(err) => {
+            error!(?err, "Failed to simulate policies");
+            Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to simulate policies",
+            ))
+        }
+    }
+}
+
+async fn run_policies_handler(
+    State(state): State<AppState>,
+) -> Result<Json<policy::PolicyRunSummary>, (StatusCode, Json<ErrorResponse>)> {
+    if state.scan_status.read().await.is_running() {
+        return Err(error_response(
+            StatusCode::CONFLICT,
+            "Cannot evaluate policies while a scan is running.",
+        ));
+    }
+
+    let config_snapshot = state.config.read().await.clone();
+    if !config::config_is_ready(&config_snapshot) {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Configuration incomplete. Please finish setup before evaluating policies.",
+        ));
+    }
+
+    match policy::run_once(&state.db, &config_snapshot).await {
+        Ok(summary) => Ok(Json(summary)),
+        Err(err) => {
+            error!(?err, "Failed to evaluate policies");
+            Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to evaluate policies",
+            ))
+        }
+    }
+}
+
+async fn run_prefetch_handler(
+    State(state): State<AppState>,
+) -> Result<Json<prefetch::PrefetchRunSummary>, (StatusCode, Json<ErrorResponse>)> {
+    if state.scan_status.read().await.is_running() {
+        return Err(error_response(
+            StatusCode::CONFLICT,
+            "Cannot prefetch while a scan is running.",
+        ));
+    }
+
+    let config_snapshot = state.config.read().await.clone();
+    if !config::config_is_ready(&config_snapshot) {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Configuration incomplete. Please finish setup before prefetching.",
+        ));
+    }
+
+    match prefetch::run_once(&state.db, &config_snapshot).await {
+        Ok(summary) => Ok(Json(summary)),
+        Err(prefetch::PrefetchError::Jellyfin(JellyfinError::NotConfigured)) => Err(
+            error_response(StatusCode::BAD_REQUEST, "Jellyfin not configured"),
+        ),
+        Err(error) => {
+            error!(%error, "Failed to run prefetch");
+            Err(error_response(
+                StatusCode::BAD_GATEWAY,
+                "Failed to run prefetch",
+            ))
+        }
+    }
+}
+
+async fn get_mergerfs_status(State(state): State<AppState>) -> Json<mergerfs::MergerfsStatus> {
+    let config = state.config.read().await;
+    Json(mergerfs::status(&config))
+}
+
+async fn check_farm_handler(
+    State(state): State<AppState>,
+) -> Result<Json<farm::FarmReport>, (StatusCode, Json<ErrorResponse>)> {
+    run_farm_reconcile(&state, true).await
+}
+
+async fn reconcile_farm_handler(
+    State(state): State<AppState>,
+) -> Result<Json<farm::FarmReport>, (StatusCode, Json<ErrorResponse>)> {
+    run_farm_reconcile(&state, false).await
+}
+
+async fn run_farm_reconcile(
+    state: &AppState,
+    dry_run: bool,
+) -> Result<Json<farm::FarmReport>, (StatusCode, Json<ErrorResponse>)> {
+    let config_snapshot = state.config.read().await.clone();
+    match farm::reconcile(&state.db, &config_snapshot, dry_run).await {
+        Ok(Some(report)) => Ok(Json(report)),
+        Ok(None) => Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Stable paths are not enabled",
+        )),
+        Err(err) => {
+            error!(?err, "Failed to reconcile stable path farm");
+            Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to reconcile stable path farm",
+            ))
+        }
+    }
+}
+
+async fn list_policy_decisions_handler(
+    State(state): State<AppState>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<Page<policy::PolicyDecisionRecord>>, (StatusCode, Json<ErrorResponse>)> {
+    let limit = query.limit.unwrap_or(50).min(500) as i64;
+    let offset = query.offset.unwrap_or(0) as i64;
+
+    match policy::list_decisions(&state.db, limit, offset).await {
+        Ok((items, total)) => Ok(Json(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })),
+        Err(err) => {
+            error!(?err, "Failed to list policy decisions");
+            Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to list policy decisions",
+            ))
+        }
+    }
+}
+
 async fn get_system_stats_history(
     State(state): State<AppState>,
     Query(query): Query<HistoryQuery>,
 ) -> Result<Json<Vec<SystemMetricsHistoryPoint>>, (StatusCode, Json<ErrorResponse>)> {
-    let seconds_ago = parse_duration(&query.duration);
-    let cutoff_timestamp = Utc::now().timestamp() - seconds_ago;
+    let cutoff_timestamp = duration_cutoff(query.duration.as_deref())?;
 
     let rows = sqlx::query_as::<_, SystemMetricsHistoryPoint>(
         r#"
@@ -1028,15 +1819,14 @@ async fn get_pool_usage_history(
     State(state): State<AppState>,
     Query(query): Query<HistoryQuery>,
 ) -> Result<Json<Vec<PoolUsageHistoryPoint>>, (StatusCode, Json<ErrorResponse>)> {
-    let seconds_ago = parse_duration(&query.duration);
-    let cutoff_timestamp = Utc::now().timestamp() - seconds_ago;
+    let cutoff_timestamp = duration_cutoff(query.duration.as_deref())?;
 
     let rows = sqlx::query_as::<_, PoolUsageHistoryPoint>(
         r#"
-        SELECT timestamp, pool_type, total_bytes, used_bytes, free_bytes
+        SELECT timestamp, tier, total_bytes, used_bytes, free_bytes
         FROM pool_usage_history
         WHERE timestamp >= ?
-        ORDER BY pool_type, timestamp ASC
+        ORDER BY tier, timestamp ASC
         "#,
     )
     .bind(cutoff_timestamp)
@@ -1057,7 +1847,17 @@ async fn get_pool_usage_history(
 
 async fn get_job_analytics(
     State(state): State<AppState>,
+    Query(query): Query<JobAnalyticsQuery>,
 ) -> Result<Json<JobAnalytics>, (StatusCode, Json<ErrorResponse>)> {
+    let bucket_seconds =
+        job_analytics::bucket_seconds(query.bucket.as_deref()).ok_or_else(|| {
+            error_response(
+                StatusCode::BAD_REQUEST,
+                "bucket must be 'hour', 'day' or 'week'",
+            )
+        })?;
+    let cutoff_timestamp = duration_cutoff(Some(query.duration.as_deref().unwrap_or("30d")))?;
+
     // Get job counts by status
     let counts = sqlx::query_as::<_, (String, i64)>(
         r#"
@@ -1081,15 +1881,30 @@ async fn get_job_analytics(
     .await
     .unwrap_or(Some(0));
 
+    let config = state.config.read().await.clone();
+    let buckets =
+        job_analytics::collect_buckets(&state.db, &config, bucket_seconds, cutoff_timestamp)
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to fetch bucketed job analytics");
+                error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to fetch job analytics",
+                )
+            })?;
+
     match counts {
         Ok(status_counts) => {
             let mut analytics = JobAnalytics {
                 total_jobs: 0,
                 running_count: 0,
                 queued_count: 0,
+                waiting_for_playback_count: 0,
                 completed_count: 0,
                 failed_count: 0,
                 total_bytes_moved: total_bytes.unwrap_or(0),
+                bucket_seconds,
+                buckets,
             };
 
             for (status, count) in status_counts {
@@ -1097,6 +1912,9 @@ async fn get_job_analytics(
                 match status.as_str() {
                     "running" => analytics.running_count = count,
                     "queued" => analytics.queued_count = count,
+                    jobs::STATUS_WAITING_FOR_PLAYBACK => {
+                        analytics.waiting_for_playback_count = count
+                    }
                     "success" => analytics.completed_count = count,
                     "failed" => analytics.failed_count = count,
                     _ => {}
@@ -1115,17 +1933,28 @@ async fn get_job_analytics(
     }
 }
 
-fn parse_duration(duration: &Option<String>) -> i64 {
-    match duration.as_deref() {
-        Some("1h") => 3600,
-        Some("6h") => 6 * 3600,
-        Some("24h") => 24 * 3600,
-        Some("7d") => 7 * 24 * 3600,
-        Some("30d") => 30 * 24 * 3600,
-        _ => 3600, // Default to 1 hour
+fn parse_duration(duration: Option<&str>) -> Option<i64> {
+    match duration {
+        None | Some("1h") => Some(3600), // Default to 1 hour
+        Some("6h") => Some(6 * 3600),
+        Some("24h") => Some(24 * 3600),
+        Some("7d") => Some(7 * 24 * 3600),
+        Some("30d") => Some(30 * 24 * 3600),
+        _ => None,
     }
 }
 
+/// Start of the window named by a `duration` query value, as a unix timestamp.
+fn duration_cutoff(duration: Option<&str>) -> Result<i64, (StatusCode, Json<ErrorResponse>)> {
+    let seconds = parse_duration(duration).ok_or_else(|| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            "duration must be '1h', '6h', '24h', '7d' or '30d'",
+        )
+    })?;
+    Ok(Utc::now().timestamp() - seconds)
+}
+
 // User settings handlers
 async fn get_user_settings_handler(
     State(state): State<AppState>,
@@ -1234,19 +2063,62 @@ fn paths_error_response(error: PathsError) -> (StatusCode, Json<ErrorResponse>)
 fn job_error_response(error: jobs::JobError) -> (StatusCode, Json<ErrorResponse>) {
     match error {
         jobs::JobError::ShowNotFound => error_response(StatusCode::NOT_FOUND, "Show not found"),
-        jobs::JobError::InvalidTarget => {
-            error_response(StatusCode::BAD_REQUEST, "target must be 'hot' or 'cold'")
-        }
+        jobs::JobError::ShowMissing => error_response(
+            StatusCode::CONFLICT,
+            "Show folder is missing from disk; rescan or purge it",
+        ),
+        jobs::JobError::InvalidTarget => error_response(
+            StatusCode::BAD_REQUEST,
+            "target must be a configured tier name",
+        ),
         jobs::JobError::AlreadyInLocation => {
-            error_response(StatusCode::BAD_REQUEST, "Show already in requested pool")
+            error_response(StatusCode::BAD_REQUEST, "Show already in requested tier")
         }
-        jobs::JobError::MissingRoot(root) => error_response(
+        jobs::JobError::MissingRoot(tier) => error_response(
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Missing configuration for {root}"),
+            format!("Missing root for tier {tier}"),
         ),
         jobs::JobError::PathMismatch => error_response(
             StatusCode::BAD_REQUEST,
-            "Show path is not within configured pools",
+            "Show path is not within a configured tier",
+        ),
+        jobs::JobError::TierFull(tier) => error_response_with_details(
+            StatusCode::CONFLICT,
+            format!("Moving this show would exceed the limits of tier {tier}"),
+            format!("tier={tier}"),
+        ),
+        jobs::JobError::ShowSplit(other) => error_response_with_details(
+            StatusCode::CONFLICT,
+            "Show folder also exists on another mergerfs branch; remove one copy first",
+            format!("other_path={other}"),
+        ),
+        jobs::JobError::JobAlreadyActive(job_id) => error_response_with_details(
+            StatusCode::CONFLICT,
+            "Show already has a queued or running job",
+            format!("job_id={job_id}"),
+        ),
+        jobs::JobError::IdempotencyKeyMismatch => error_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Idempotency-Key was already used for a different show or target",
+        ),
+        jobs::JobError::ShowInUse => {
+            error_response(StatusCode::CONFLICT, "Show is currently being streamed")
+        }
+        jobs::JobError::ShowPinned(pinned) => error_response_with_details(
+            StatusCode::CONFLICT,
+            format!("Show is pinned to {pinned}; set force to move it anyway"),
+            format!("pinned_location={pinned}"),
+        ),
+        jobs::JobError::QuotaExceeded(exceeded) => error_response_with_details(
+            StatusCode::CONFLICT,
+            format!(
+                "Moving this show would exceed the {} quota of {}",
+                exceeded.tier, exceeded.library
+            ),
+            format!(
+                "tier={} used_bytes={} requested_bytes={} limit_bytes={}",
+                exceeded.tier, exceeded.used_bytes, exceeded.requested_bytes, exceeded.limit_bytes
+            ),
         ),
         jobs::JobError::Database(err) => {
             error!(?err, "Database error while processing job request");
@@ -1258,4 +2130,3 @@ fn job_error_response(error: jobs::JobError) -> (StatusCode, Json<ErrorResponse>
         }
     }
 }
-
@@ -0,0 +1,229 @@
+use serde::Serialize;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn};
+
+use crate::config::{Config, TierConfig};
+
+/// Control file mergerfs exposes at the root of every union.
+const CONTROL_FILE: &str = ".mergerfs";
+const BRANCHES_XATTR: &str = "user.mergerfs.branches";
+
+/// The union as JellyMover sees it, for `GET /api/mergerfs`.
+#[derive(Debug, Serialize)]
+pub struct MergerfsStatus {
+    pub enabled: bool,
+    pub union_root: String,
+    /// `config` when branches are listed in the config, `mount` when read from the union.
+    pub source: &'static str,
+    pub branches: Vec<BranchStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BranchStatus {
+    pub path: String,
+    /// Tiers with a root inside this branch.
+    pub tiers: Vec<String>,
+}
+
+/// Reads the branch list of the union mounted at `union_root`.
+pub fn read_branches(union_root: &Path) -> io::Result<Vec<PathBuf>> {
+    let raw = xattr::get(union_root.join(CONTROL_FILE), BRANCHES_XATTR)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "mergerfs control file has no branch list",
+        )
+    })?;
+    Ok(parse_branches(&String::from_utf8_lossy(&raw)))
+}
+
+/// Parses mergerfs' `/path=MODE[,MINFREESPACE]:...` branch list.
+pub fn parse_branches(raw: &str) -> Vec<PathBuf> {
+    raw.trim()
+        .split(':')
+        .filter_map(|entry| {
+            let entry = entry.trim();
+            let path = match entry.rsplit_once('=') {
+                Some((path, mode)) if is_branch_mode(mode) => path,
+                _ => entry,
+            };
+            (!path.is_empty()).then(|| PathBuf::from(path))
+        })
+        .collect()
+}
+
+fn is_branch_mode(mode: &str) -> bool {
+    matches!(mode.split(',').next(), Some("RW" | "RO" | "NC"))
+}
+
+/// Reads the branches from the mount when none are configured, and creates one
+/// tier per branch, in union order, when no tiers exist yet. Returns whether
+/// tiers were created so the caller can save the config.
+pub fn detect_branches(config: &mut Config) -> bool {
+    config.mergerfs.detected_branches.clear();
+    let Some(union_root) = config.mergerfs.union_root_path() else {
+        return false;
+    };
+
+    if config
+        .mergerfs
+        .branches
+        .iter()
+        .all(|branch| branch.trim().is_empty())
+    {
+        match read_branches(&union_root) {
+            Ok(branches) => config.mergerfs.detected_branches = branches,
+            Err(err) => warn!(
+                %err,
+                union_root = %union_root.display(),
+                "Failed to read mergerfs branches from the union"
+            ),
+        }
+    }
+
+    let branches = config.mergerfs.branch_paths();
+    if !config.tiers.is_empty() || branches.len() < 2 {
+        return false;
+    }
+    let mut tiers: Vec<TierConfig> = Vec::new();
+    for (index, branch) in branches.iter().enumerate() {
+        let mut name = tier_name(branch).unwrap_or_else(|| format!("branch-{}", index + 1));
+        if tiers.iter().any(|tier| tier.name == name) {
+            name = format!("{name}-{}", index + 1);
+        }
+        tiers.push(TierConfig {
+            name,
+            roots: vec![branch.display().to_string()],
+            ..TierConfig::default()
+        });
+    }
+    info!(
+        tiers = tiers.len(),
+        "Created storage tiers from mergerfs branches"
+    );
+    config.tiers = tiers;
+    true
+}
+
+/// Tier names only allow letters, digits, `-` and `_`.
+fn tier_name(branch: &Path) -> Option<String> {
+    let name: String = branch
+        .file_name()?
+        .to_string_lossy()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// The path of `path` inside the union, where Jellyfin sees it.
+pub fn union_path_for(config: &Config, path: &Path) -> Option<PathBuf> {
+    let union_root = config.mergerfs.union_root_path()?;
+    let relative = config
+        .mergerfs
+        .branch_paths()
+        .into_iter()
+        .filter_map(|branch| path.strip_prefix(branch).ok().map(Path::to_path_buf))
+        .min_by_key(|relative| relative.components().count())?;
+    Some(union_root.join(relative))
+}
+
+/// The folders on each branch behind `path` when it lies inside the union;
+/// `None` otherwise.
+pub fn branch_paths_for(config: &Config, path: &Path) -> Option<Vec<PathBuf>> {
+    let union_root = config.mergerfs.union_root_path()?;
+    let relative = path.strip_prefix(&union_root).ok()?;
+    Some(
+        config
+            .mergerfs
+            .branch_paths()
+            .into_iter()
+            .map(|branch| {
+                if relative.as_os_str().is_empty() {
+                    branch
+                } else {
+                    branch.join(relative)
+                }
+            })
+            .collect(),
+    )
+}
+
+pub fn status(config: &Config) -> MergerfsStatus {
+    let configured = config
+        .mergerfs
+        .branches
+        .iter()
+        .any(|branch| !branch.trim().is_empty());
+    let branches = config
+        .mergerfs
+        .branch_paths()
+        .into_iter()
+        .map(|branch| BranchStatus {
+            path: branch.display().to_string(),
+            tiers: config
+                .tiers
+                .iter()
+                .filter(|tier| {
+                    tier.root_paths()
+                        .iter()
+                        .any(|root| root.starts_with(&branch))
+                })
+                .map(|tier| tier.name.clone())
+                .collect(),
+        })
+        .collect();
+
+    MergerfsStatus {
+        enabled: config.mergerfs.enabled,
+        union_root: config.mergerfs.union_root.trim().to_string(),
+        source: if configured { "config" } else { "mount" },
+        branches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_branch_modes_and_maps_paths_through_the_union() {
+        assert_eq!(
+            parse_branches("/mnt/ssd=RW:/mnt/disk 1=NC,200G:/mnt/a=b\n"),
+            vec![
+                PathBuf::from("/mnt/ssd"),
+                PathBuf::from("/mnt/disk 1"),
+                PathBuf::from("/mnt/a=b")
+            ]
+        );
+
+        let mut config = Config::default();
+        config.mergerfs.enabled = true;
+        config.mergerfs.union_root = "/mnt/media".to_string();
+        config.mergerfs.branches = vec!["/mnt/ssd".to_string(), "/mnt/disk 1".to_string()];
+        assert!(detect_branches(&mut config));
+        let names: Vec<_> = config.tiers.iter().map(|tier| tier.name.as_str()).collect();
+        assert_eq!(names, vec!["ssd", "disk-1"]);
+
+        assert_eq!(
+            union_path_for(&config, Path::new("/mnt/disk 1/tv/Severance")),
+            Some(PathBuf::from("/mnt/media/tv/Severance"))
+        );
+        assert_eq!(
+            branch_paths_for(&config, Path::new("/mnt/media/tv")),
+            Some(vec![
+                PathBuf::from("/mnt/ssd/tv"),
+                PathBuf::from("/mnt/disk 1/tv")
+            ])
+        );
+        assert_eq!(branch_paths_for(&config, Path::new("/srv/tv")), None);
+    }
+}
@@ -78,47 +78,27 @@ async fn collect_pool_metrics(db: &SqlitePool, config: &Arc<RwLock<Config>>) {
     let config_snapshot = config.read().await;
     let timestamp = Utc::now().timestamp();
 
-    // Collect hot pool metrics
-    if let Some(hot_pool) = pools::collect_pool_usage(&config_snapshot.hot_root) {
+    for tier in &config_snapshot.tiers {
+        let Some(pool) = pools::tier_usage(tier).await else {
+            continue;
+        };
         let result = sqlx::query(
             r#"
             INSERT INTO pool_usage_history
-            (timestamp, pool_type, total_bytes, used_bytes, free_bytes)
+            (timestamp, tier, total_bytes, used_bytes, free_bytes)
             VALUES (?, ?, ?, ?, ?)
             "#,
         )
         .bind(timestamp)
-        .bind("hot")
-        .bind(hot_pool.total_bytes as i64)
-        .bind(hot_pool.used_bytes as i64)
-        .bind(hot_pool.free_bytes as i64)
+        .bind(&tier.name)
+        .bind(pool.total_bytes as i64)
+        .bind(pool.used_bytes as i64)
+        .bind(pool.free_bytes as i64)
         .execute(db)
         .await;
 
         if let Err(err) = result {
-            error!(?err, "Failed to record hot pool metrics");
-        }
-    }
-
-    // Collect cold pool metrics
-    if let Some(cold_pool) = pools::collect_pool_usage(&config_snapshot.cold_root) {
-        let result = sqlx::query(
-            r#"
-            INSERT INTO pool_usage_history
-            (timestamp, pool_type, total_bytes, used_bytes, free_bytes)
-            VALUES (?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(timestamp)
-        .bind("cold")
-        .bind(cold_pool.total_bytes as i64)
-        .bind(cold_pool.used_bytes as i64)
-        .bind(cold_pool.free_bytes as i64)
-        .execute(db)
-        .await;
-
-        if let Err(err) = result {
-            error!(?err, "Failed to record cold pool metrics");
+            error!(?err, tier = %tier.name, "Failed to record tier usage metrics");
         }
     }
 }
@@ -0,0 +1,293 @@
+use quick_xml::de::from_str;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+use tracing::warn;
+
+/// Metadata from a show's `tvshow.nfo` or a movie's NFO, as written by
+/// Jellyfin or Kodi.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ShowNfo {
+    pub title: Option<String>,
+    pub original_title: Option<String>,
+    pub year: Option<i64>,
+    pub premiered: Option<String>,
+    pub end_date: Option<String>,
+    pub rating: Option<f64>,
+    /// E.g. `Continuing` or `Ended`.
+    pub status: Option<String>,
+    pub genres: Vec<String>,
+    pub studios: Vec<String>,
+    pub tags: Vec<String>,
+    /// Provider name to id, e.g. `tvdb` → `81189`.
+    pub provider_ids: BTreeMap<String, String>,
+}
+
+/// The `<tvshow>` (or `<movie>`) document as written. Every value is read as text so one
+/// malformed field does not sink the rest.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawShow {
+    title: Option<String>,
+    originaltitle: Option<String>,
+    year: Option<String>,
+    premiered: Option<String>,
+    enddate: Option<String>,
+    rating: Option<String>,
+    status: Option<String>,
+    genre: Vec<String>,
+    studio: Vec<String>,
+    tag: Vec<String>,
+    uniqueid: Vec<UniqueId>,
+    tmdbid: Option<String>,
+    tvdbid: Option<String>,
+    imdb_id: Option<String>,
+    anidbid: Option<String>,
+    anilistid: Option<String>,
+}
+
+/// `<uniqueid type="tvdb">81189</uniqueid>`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct UniqueId {
+    #[serde(rename = "@type")]
+    kind: Option<String>,
+    #[serde(rename = "$text")]
+    value: Option<String>,
+}
+
+/// Metadata from an episode's NFO, which sits beside its video.
+#[derive(Debug, Default, Clone)]
+pub struct EpisodeNfo {
+    pub title: Option<String>,
+    pub aired: Option<String>,
+    pub runtime_minutes: Option<i64>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    /// Video bitrate in bits per second.
+    pub bitrate: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawEpisode {
+    title: Option<String>,
+    aired: Option<String>,
+    runtime: Option<String>,
+    fileinfo: Option<RawFileInfo>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawFileInfo {
+    streamdetails: RawStreamDetails,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawStreamDetails {
+    video: Vec<RawStream>,
+    audio: Vec<RawStream>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RawStream {
+    codec: Option<String>,
+    bitrate: Option<String>,
+    width: Option<String>,
+    height: Option<String>,
+    durationinseconds: Option<String>,
+    default: Option<String>,
+}
+
+/// Reads `tvshow.nfo` in `show_path`. A missing file gives `None`; one that
+/// does not parse is logged and also gives `None`.
+pub fn read_show_nfo(show_path: &Path) -> Option<ShowNfo> {
+    let path = show_path.join("tvshow.nfo");
+    let content = fs::read_to_string(&path).ok()?;
+    match parse_show(&content) {
+        Ok(nfo) => Some(nfo),
+        Err(err) => {
+            warn!(path = %path.display(), "Failed to parse tvshow.nfo: {err}");
+            None
+        }
+    }
+}
+
+/// Reads a movie's NFO at `path`, which has the `tvshow.nfo` layout under a
+/// `<movie>` root. Missing or unparsable files give `None`.
+pub fn read_movie_nfo(path: &Path) -> Option<ShowNfo> {
+    let content = fs::read_to_string(path).ok()?;
+    match parse_show(&content) {
+        Ok(nfo) => Some(nfo),
+        Err(err) => {
+            warn!(path = %path.display(), "Failed to parse movie NFO: {err}");
+            None
+        }
+    }
+}
+
+pub fn parse_show(content: &str) -> Result<ShowNfo, quick_xml::DeError> {
+    let raw: RawShow = from_str(content.trim_start_matches('\u{feff}'))?;
+
+    let mut provider_ids = BTreeMap::new();
+    for (provider, id) in [
+        ("tmdb", raw.tmdbid),
+        ("tvdb", raw.tvdbid),
+        ("imdb", raw.imdb_id),
+        ("anidb", raw.anidbid),
+        ("anilist", raw.anilistid),
+    ] {
+        if let Some(id) = clean(id) {
+            provider_ids.insert(provider.to_string(), id);
+        }
+    }
+    for unique in raw.uniqueid {
+        if let (Some(kind), Some(id)) = (clean(unique.kind), clean(unique.value)) {
+            provider_ids.entry(kind.to_lowercase()).or_insert(id);
+        }
+    }
+
+    let premiered = clean(raw.premiered);
+    // Some writers leave out <year>; the premiere date carries it too.
+    let year = clean(raw.year)
+        .or_else(|| {
+            premiered
+                .as_ref()
+                .and_then(|date| date.get(..4))
+                .map(str::to_string)
+        })
+        .and_then(|year| year.parse().ok());
+
+    Ok(ShowNfo {
+        title: clean(raw.title),
+        original_title: clean(raw.originaltitle),
+        year,
+        premiered,
+        end_date: clean(raw.enddate),
+        rating: clean(raw.rating).and_then(|rating| rating.parse().ok()),
+        status: clean(raw.status),
+        genres: clean_list(raw.genre),
+        studios: clean_list(raw.studio),
+        tags: clean_list(raw.tag),
+        provider_ids,
+    })
+}
+
+/// Reads the episode NFO at `path`, logging files that do not parse.
+pub fn read_episode_nfo(path: &Path) -> Option<EpisodeNfo> {
+    let content = fs::read_to_string(path).ok()?;
+    match parse_episode(&content) {
+        Ok(nfo) => Some(nfo),
+        Err(err) => {
+            warn!(path = %path.display(), "Failed to parse episode NFO: {err}");
+            None
+        }
+    }
+}
+
+pub fn parse_episode(content: &str) -> Result<EpisodeNfo, quick_xml::DeError> {
+    let raw: RawEpisode = from_str(content.trim_start_matches('\u{feff}'))?;
+    let streams = raw.fileinfo.unwrap_or_default().streamdetails;
+    let video = streams.video.into_iter().next().unwrap_or_default();
+    // The default audio track, or the first one.
+    let audio_index = streams
+        .audio
+        .iter()
+        .position(|audio| {
+            audio
+                .default
+                .as_deref()
+                .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"))
+        })
+        .unwrap_or(0);
+    let audio = streams
+        .audio
+        .into_iter()
+        .nth(audio_index)
+        .unwrap_or_default();
+
+    let number = |value: Option<String>| clean(value).and_then(|value| value.parse::<i64>().ok());
+    let runtime_minutes = number(raw.runtime)
+        .or_else(|| number(video.durationinseconds).map(|seconds| (seconds + 30) / 60));
+
+    Ok(EpisodeNfo {
+        title: clean(raw.title),
+        aired: clean(raw.aired),
+        runtime_minutes,
+        video_codec: clean(video.codec),
+        audio_codec: clean(audio.codec),
+        width: number(video.width),
+        height: number(video.height),
+        bitrate: number(video.bitrate),
+    })
+}
+
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Trims values and drops blanks and repeats, keeping the NFO's order.
+fn clean_list(values: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for value in values {
+        let value = value.trim();
+        if !value.is_empty() && !cleaned.iter().any(|seen| seen.eq_ignore_ascii_case(value)) {
+            cleaned.push(value.to_string());
+        }
+    }
+    cleaned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sample_tvshow_nfo() {
+        let nfo = read_show_nfo(Path::new("../Example")).unwrap();
+        assert_eq!(nfo.title.as_deref(), Some("Summer Time Rendering"));
+        assert_eq!(nfo.original_title.as_deref(), Some("Summer Time Render"));
+        assert_eq!(nfo.year, Some(2022));
+        assert_eq!(nfo.end_date.as_deref(), Some("2022-09-30"));
+        assert_eq!(nfo.rating, Some(8.4));
+        assert_eq!(nfo.status.as_deref(), Some("Ended"));
+        assert_eq!(nfo.genres.first().map(String::as_str), Some("Anime"));
+        assert_eq!(nfo.genres.len(), 5);
+        assert_eq!(nfo.studios.len(), 8);
+        assert!(nfo.tags.iter().any(|tag| tag == "Time Loop"));
+        assert_eq!(
+            nfo.provider_ids.get("anidb").map(String::as_str),
+            Some("16033")
+        );
+
+        let nfo = parse_show(
+            r#"<tvshow><genre>Drama</genre><year></year><premiered>2019-11-12</premiered>
+               <uniqueid type="TVDB">81189</uniqueid><genre> drama </genre><rating>n/a</rating></tvshow>"#,
+        )
+        .unwrap();
+        assert_eq!(nfo.genres, vec!["Drama"]);
+        assert_eq!(nfo.year, Some(2019));
+        assert_eq!(nfo.rating, None);
+        assert_eq!(
+            nfo.provider_ids.get("tvdb").map(String::as_str),
+            Some("81189")
+        );
+    }
+
+    #[test]
+    fn parses_sample_episode_nfo() {
+        let nfo = read_episode_nfo(Path::new("../Example/episode_name.nfo")).unwrap();
+        assert_eq!(nfo.title.as_deref(), Some("Goodbye, Summer Days"));
+        assert_eq!(nfo.aired.as_deref(), Some("2022-04-15"));
+        assert_eq!(nfo.runtime_minutes, Some(25));
+        assert_eq!(nfo.video_codec.as_deref(), Some("h264"));
+        assert_eq!(nfo.audio_codec.as_deref(), Some("dts"));
+        assert_eq!((nfo.width, nfo.height), (Some(1920), Some(1080)));
+        assert_eq!(nfo.bitrate, Some(28_995_745));
+    }
+}
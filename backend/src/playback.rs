@@ -0,0 +1,86 @@
+use std::path::Path;
+use tracing::warn;
+
+use crate::{
+    config::{Config, PlaybackGuardMode},
+    farm,
+    jellyfin::{JellyfinClient, JellyfinError},
+    mergerfs,
+};
+
+/// Whether Jellyfin is currently streaming anything from the show at `show_path`.
+///
+/// Always `false` when the guard is set to ignore playback or Jellyfin is not
+/// configured. Session lookup failures are logged and treated as "not in use"
+/// so an unreachable Jellyfin cannot stall the job queue.
+pub async fn show_in_use(config: &Config, show_path: &str) -> bool {
+    if config.playback_guard.mode == PlaybackGuardMode::Ignore {
+        return false;
+    }
+
+    let client = match JellyfinClient::from_config(config) {
+        Ok(client) => client,
+        Err(JellyfinError::NotConfigured) => return false,
+        Err(err) => {
+            warn!(%err, "Could not create Jellyfin client for playback check");
+            return false;
+        }
+    };
+
+    // With stable paths or a mergerfs union, Jellyfin reads the show elsewhere.
+    let aliases: Vec<_> = [
+        farm::link_for(config, Path::new(show_path)),
+        mergerfs::union_path_for(config, Path::new(show_path)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    match client.now_playing_paths().await {
+        Ok(paths) => paths.iter().any(|item_path| {
+            item_belongs_to_show(item_path, show_path)
+                || aliases
+                    .iter()
+                    .any(|alias| Path::new(item_path).starts_with(alias))
+        }),
+        Err(err) => {
+            warn!(%err, show_path, "Failed to query Jellyfin sessions; assuming show is idle");
+            false
+        }
+    }
+}
+
+/// Jellyfin may mount the library elsewhere, so besides a plain prefix match an
+/// item counts when one of its parent folders has the show folder's name.
+pub fn item_belongs_to_show(item_path: &str, show_path: &str) -> bool {
+    let item = Path::new(item_path);
+    let show = Path::new(show_path.trim_end_matches('/'));
+    if item.starts_with(show) {
+        return true;
+    }
+
+    let Some(show_folder) = show.file_name() else {
+        return false;
+    };
+    item.parent()
+        .is_some_and(|parent| parent.iter().any(|component| component == show_folder))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_items_under_the_show_folder_on_any_mount() {
+        let show = "/media/hot/Frieren";
+        assert!(item_belongs_to_show("/media/hot/Frieren/S01/E01.mkv", show));
+        assert!(item_belongs_to_show(
+            "/data/tv/Frieren/Season 1/E01.mkv",
+            show
+        ));
+        assert!(!item_belongs_to_show(
+            "/media/hot/Frieren 2/S01/E01.mkv",
+            show
+        ));
+        assert!(!item_belongs_to_show("/data/movies/Frieren", show));
+    }
+}
@@ -0,0 +1,853 @@
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::FromRow;
+use std::{collections::HashMap, sync::Arc};
+use tokio::{
+    sync::RwLock,
+    task::JoinHandle,
+    time::{Duration, sleep},
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{self, Config, PolicyCondition, PolicyRule, TierConfig, TierLimits},
+    db::DbPool,
+    jobs::{self, JobError},
+    pools,
+    quotas::QuotaTracker,
+    scanner::ScanStatus,
+};
+
+pub const ACTION_QUEUED: &str = "queued";
+pub const ACTION_FAILED: &str = "failed";
+
+/// The per-show inputs policy conditions are evaluated against.
+#[derive(Debug, Clone, FromRow)]
+pub struct ShowFacts {
+    pub id: i64,
+    pub title: Option<String>,
+    pub path: String,
+    pub location: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub first_seen_at: Option<i64>,
+    pub episodes_added_at: Option<i64>,
+    pub last_played_at: Option<i64>,
+    /// From the show's `tvshow.nfo`.
+    pub rating: Option<f64>,
+    /// Tier the show must stay in; automatic tiering never moves it elsewhere.
+    pub pinned_location: Option<String>,
+    pub has_active_job: bool,
+}
+
+/// A move the rules ask for; produced identically for real runs and previews.
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyDecision {
+    pub show_id: i64,
+    pub show_title: Option<String>,
+    pub rule_name: String,
+    pub source_location: Option<String>,
+    pub target: String,
+    pub size_bytes: i64,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PolicyDecisionRecord {
+    pub id: i64,
+    pub created_at: i64,
+    pub show_id: i64,
+    pub show_title: Option<String>,
+    pub rule_name: String,
+    pub source_location: Option<String>,
+    pub target: String,
+    pub action: String,
+    pub job_id: Option<i64>,
+    pub reason: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PolicyRunSummary {
+    pub evaluated: usize,
+    pub matched: usize,
+    pub queued: usize,
+    pub failed: usize,
+    /// Moves left out because they would exceed a library's quota in their target tier.
+    pub over_quota: usize,
+}
+
+/// A tier's capacity and the bytes it will hold once queued or running jobs finish.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ProjectedUsage {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+}
+
+impl ProjectedUsage {
+    pub fn percent(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        self.used_bytes as f64 * 100.0 / self.total_bytes as f64
+    }
+
+    /// Usage after `delta` bytes arrive (positive) or leave (negative).
+    pub fn shifted(self, delta: i64) -> Self {
+        Self {
+            used_bytes: self.used_bytes.saturating_add_signed(delta),
+            ..self
+        }
+    }
+
+    /// Whether `incoming_bytes` more would break `limits`.
+    pub fn exceeds(self, limits: &TierLimits, incoming_bytes: i64) -> bool {
+        let after = self.shifted(incoming_bytes);
+        let free_after = after.total_bytes.saturating_sub(after.used_bytes);
+        limits
+            .max_used_percent
+            .is_some_and(|max| after.percent() > max)
+            || limits.min_free_bytes.is_some_and(|min| free_after < min)
+    }
+}
+
+/// A tier's projected usage; `None` when the tier cannot be inspected.
+#[derive(Debug, Clone, Serialize)]
+pub struct TierProjection {
+    pub tier: String,
+    pub usage: Option<ProjectedUsage>,
+}
+
+/// One simulated move and the usage of the tiers it leaves and enters right after it.
+#[derive(Debug, Serialize)]
+pub struct SimulatedMove {
+    #[serde(flatten)]
+    pub decision: PolicyDecision,
+    pub source_after: Option<ProjectedUsage>,
+    pub target_after: Option<ProjectedUsage>,
+}
+
+/// A move the engine would try but `create_move_job` would refuse.
+#[derive(Debug, Serialize)]
+pub struct RefusedMove {
+    #[serde(flatten)]
+    pub decision: PolicyDecision,
+    pub reason: String,
+}
+
+/// What a rule set would do right now, without queuing anything.
+#[derive(Debug, Serialize)]
+pub struct PolicySimulation {
+    pub evaluated: usize,
+    /// Moves left out because they would exceed a library's quota in their target tier.
+    pub over_quota: usize,
+    /// Moves the engine would queue on its next pass; the rest follow on later passes.
+    pub next_run_count: usize,
+    /// Bytes moving to a faster tier.
+    pub bytes_promoted: i64,
+    /// Bytes moving to a slower tier.
+    pub bytes_demoted: i64,
+    /// Every configured tier, fastest first.
+    pub before: Vec<TierProjection>,
+    pub after: Vec<TierProjection>,
+    pub moves: Vec<SimulatedMove>,
+    /// Moves refused because the target tier would break its `limits`.
+    pub refused: Vec<RefusedMove>,
+}
+
+/// Start the background task that periodically applies `Config::policies`.
+pub fn start_engine(
+    db: DbPool,
+    config: Arc<RwLock<Config>>,
+    scan_status: Arc<RwLock<ScanStatus>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Starting tiering policy engine background task");
+
+        loop {
+            let interval_minutes = config.read().await.policies.interval_minutes.max(1);
+            sleep(Duration::from_secs(u64::from(interval_minutes) * 60)).await;
+
+            let config_snapshot = config.read().await.clone();
+            if !config_snapshot.policies.enabled || !config::config_is_ready(&config_snapshot) {
+                continue;
+            }
+            if scan_status.read().await.is_running() {
+                info!("Skipping policy evaluation while a scan is running");
+                continue;
+            }
+
+            match run_once(&db, &config_snapshot).await {
+                Ok(summary) => info!(
+                    evaluated = summary.evaluated,
+                    matched = summary.matched,
+                    queued = summary.queued,
+                    failed = summary.failed,
+                    "Policy evaluation finished"
+                ),
+                Err(err) => error!(?err, "Policy evaluation failed"),
+            }
+        }
+    })
+}
+
+/// Evaluates the configured rules once and queues the resulting moves.
+pub async fn run_once(db: &DbPool, config: &Config) -> Result<PolicyRunSummary, sqlx::Error> {
+    let shows = load_show_facts(db).await?;
+    let decisions = evaluate(&config.policies.rules, &shows, Utc::now().timestamp());
+    let matched = decisions.len();
+    let mut quotas = QuotaTracker::load(db, config).await?;
+    let (decisions, over_quota) = apply_quotas(decisions, &shows, &mut quotas);
+
+    let mut summary = PolicyRunSummary {
+        evaluated: shows.len(),
+        matched,
+        over_quota,
+        ..PolicyRunSummary::default()
+    };
+
+    let limit = config.policies.max_jobs_per_run as usize;
+    for decision in decisions.into_iter().take(limit) {
+        match jobs::create_move_job(db, config, decision.show_id, &decision.target, None, false)
+            .await
+        {
+            Ok(job) => {
+                info!(
+                    show_id = decision.show_id,
+                    job_id = job.id,
+                    rule = %decision.rule_name,
+                    target = %decision.target,
+                    reason = %decision.reason,
+                    "Policy queued move"
+                );
+                record_decision(db, &decision, ACTION_QUEUED, Some(job.id), None).await?;
+                summary.queued += 1;
+            }
+            Err(JobError::Database(err)) => return Err(err),
+            Err(err) => {
+                warn!(
+                    show_id = decision.show_id,
+                    rule = %decision.rule_name,
+                    ?err,
+                    "Policy could not queue move"
+                );
+                if !latest_decision_failed(db, &decision).await? {
+                    let note = format!("{err:?}");
+                    record_decision(db, &decision, ACTION_FAILED, None, Some(&note)).await?;
+                }
+                summary.failed += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Evaluates `rules` against the current library exactly as `run_once` would,
+/// and projects tier usage after every resulting move instead of queuing it.
+pub async fn simulate_rules(
+    db: &DbPool,
+    config: &Config,
+    rules: &[PolicyRule],
+) -> Result<PolicySimulation, sqlx::Error> {
+    let shows = load_show_facts(db).await?;
+    let mut before = Vec::with_capacity(config.tiers.len());
+    for tier in &config.tiers {
+        before.push(TierProjection {
+            tier: tier.name.clone(),
+            usage: projected_usage(db, tier).await?,
+        });
+    }
+    let decisions = evaluate(rules, &shows, Utc::now().timestamp());
+    let mut quotas = QuotaTracker::load(db, config).await?;
+    let (decisions, over_quota) = apply_quotas(decisions, &shows, &mut quotas);
+
+    Ok(PolicySimulation {
+        over_quota,
+        ..simulate(
+            decisions,
+            shows.len(),
+            config.policies.max_jobs_per_run as usize,
+            &config.tiers,
+            before,
+        )
+    })
+}
+
+/// Drops moves that would push a library over its quota in the target tier,
+/// letting earlier moves out of that tier free up room. Returns the kept
+/// decisions and how many were dropped.
+pub fn apply_quotas(
+    decisions: Vec<PolicyDecision>,
+    shows: &[ShowFacts],
+    quotas: &mut QuotaTracker,
+) -> (Vec<PolicyDecision>, usize) {
+    let paths: HashMap<i64, &str> = shows
+        .iter()
+        .map(|show| (show.id, show.path.as_str()))
+        .collect();
+    let mut dropped = 0;
+
+    let kept = decisions
+        .into_iter()
+        .filter(|decision| {
+            let Some(path) = paths.get(&decision.show_id) else {
+                return true;
+            };
+            match quotas.check(path, &decision.target, decision.size_bytes) {
+                Ok(()) => {
+                    quotas.add(path, &decision.target, decision.size_bytes);
+                    if let Some(source) = decision.source_location.as_deref() {
+                        quotas.add(path, source, -decision.size_bytes);
+                    }
+                    true
+                }
+                Err(exceeded) => {
+                    info!(
+                        show_id = decision.show_id,
+                        library = %exceeded.library,
+                        tier = %exceeded.tier,
+                        "Policy move skipped: library tier quota exceeded"
+                    );
+                    dropped += 1;
+                    false
+                }
+            }
+        })
+        .collect();
+
+    (kept, dropped)
+}
+
+/// Replays `decisions` in order against the starting usage of each tier.
+/// `before` lists the tiers fastest first; moves between tiers missing from
+/// it count toward neither byte total. A move that would break its target
+/// tier's `limits` at that point is refused, as `create_move_job` would.
+pub fn simulate(
+    decisions: Vec<PolicyDecision>,
+    evaluated: usize,
+    max_jobs_per_run: usize,
+    tiers: &[TierConfig],
+    before: Vec<TierProjection>,
+) -> PolicySimulation {
+    let mut after = before.clone();
+    let mut bytes_promoted = 0;
+    let mut bytes_demoted = 0;
+    let mut next_run_count = 0;
+    let mut moves = Vec::new();
+    let mut refused = Vec::new();
+    let rank = |name: Option<&str>| {
+        before
+            .iter()
+            .position(|tier| Some(tier.tier.as_str()) == name)
+    };
+
+    for (index, decision) in decisions.into_iter().enumerate() {
+        let size = decision.size_bytes;
+        let source = rank(decision.source_location.as_deref());
+        let target = rank(Some(decision.target.as_str()));
+
+        let limits = tiers
+            .iter()
+            .find(|tier| tier.name == decision.target)
+            .map(|tier| &tier.limits);
+        let target_usage = target.and_then(|target| after[target].usage);
+        if let (Some(limits), Some(usage)) = (limits, target_usage)
+            && usage.exceeds(limits, size)
+        {
+            let reason = JobError::TierFull(decision.target.clone()).to_string();
+            refused.push(RefusedMove { decision, reason });
+            continue;
+        }
+
+        match (source, target) {
+            (Some(source), Some(target)) if target < source => bytes_promoted += size,
+            (Some(source), Some(target)) if target > source => bytes_demoted += size,
+            _ => {}
+        }
+        // The engine tries the first `max_jobs_per_run` decisions, refused ones included.
+        if index < max_jobs_per_run {
+            next_run_count += 1;
+        }
+
+        let mut shift = |index: Option<usize>, delta: i64| {
+            let tier = &mut after[index?];
+            tier.usage = tier.usage.map(|usage| usage.shifted(delta));
+            tier.usage
+        };
+        let source_after = shift(source, -size);
+        let target_after = shift(target, size);
+
+        moves.push(SimulatedMove {
+            decision,
+            source_after,
+            target_after,
+        });
+    }
+
+    PolicySimulation {
+        evaluated,
+        over_quota: 0,
+        next_run_count,
+        bytes_promoted,
+        bytes_demoted,
+        before,
+        after,
+        moves,
+        refused,
+    }
+}
+
+/// Current usage of `tier` across its roots, adjusted for queued and running jobs.
+///
+/// Returns `None` when the tier cannot be inspected.
+pub async fn projected_usage(
+    db: &DbPool,
+    tier: &TierConfig,
+) -> Result<Option<ProjectedUsage>, sqlx::Error> {
+    let Some(pool) = pools::tier_usage(tier).await else {
+        return Ok(None);
+    };
+
+    let mut usage = ProjectedUsage {
+        total_bytes: pool.total_bytes,
+        used_bytes: pool.used_bytes,
+    };
+    for root in &tier.roots {
+        let (outgoing, incoming) =
+            jobs::pending_transfer_bytes(db, &jobs::root_prefix(root)).await?;
+        usage = usage.shifted(incoming - outgoing);
+    }
+    Ok(Some(usage))
+}
+
+/// Applies `rules` to `shows` without side effects.
+///
+/// For each show the first enabled rule whose conditions all hold decides the
+/// target; shows already in that tier, pinned to another tier or with an
+/// active job yield no decision.
+pub fn evaluate(rules: &[PolicyRule], shows: &[ShowFacts], now: i64) -> Vec<PolicyDecision> {
+    let mut decisions = Vec::new();
+
+    for show in shows.iter().filter(|show| !show.has_active_job) {
+        let Some(rule) = rules.iter().find(|rule| {
+            rule.enabled
+                && rule
+                    .conditions
+                    .iter()
+                    .all(|condition| condition_matches(condition, show, now))
+        }) else {
+            continue;
+        };
+
+        let target = rule.target.trim().to_string();
+        if show.location.as_deref() == Some(target.as_str()) {
+            continue;
+        }
+        if show
+            .pinned_location
+            .as_deref()
+            .is_some_and(|pinned| pinned != target)
+        {
+            continue;
+        }
+
+        let reason = rule
+            .conditions
+            .iter()
+            .map(describe_condition)
+            .collect::<Vec<_>>()
+            .join(" and ");
+
+        decisions.push(PolicyDecision {
+            show_id: show.id,
+            show_title: show.title.clone(),
+            rule_name: rule.name.clone(),
+            source_location: show.location.clone(),
+            target,
+            size_bytes: show.size_bytes.unwrap_or(0).max(0),
+            reason,
+        });
+    }
+
+    decisions
+}
+
+fn condition_matches(condition: &PolicyCondition, show: &ShowFacts, now: i64) -> bool {
+    match condition {
+        PolicyCondition::NotPlayedForDays { days } => show
+            .last_played_at
+            .or(show.first_seen_at)
+            .is_some_and(|since| now - since >= days_to_secs(*days)),
+        PolicyCondition::EpisodeAddedWithinDays { days } => show
+            .episodes_added_at
+            .is_some_and(|added| now - added <= days_to_secs(*days)),
+        PolicyCondition::AddedWithinDays { days } => show
+            .first_seen_at
+            .is_some_and(|added| now - added <= days_to_secs(*days)),
+        PolicyCondition::SizeAboveBytes { bytes } => show
+            .size_bytes
+            .is_some_and(|size| size.max(0) as u64 > *bytes),
+        PolicyCondition::SizeBelowBytes { bytes } => show
+            .size_bytes
+            .is_some_and(|size| (size.max(0) as u64) < *bytes),
+        PolicyCondition::RatingBelow { rating } => show.rating.is_some_and(|value| value < *rating),
+        PolicyCondition::RatingAbove { rating } => show.rating.is_some_and(|value| value > *rating),
+    }
+}
+
+fn describe_condition(condition: &PolicyCondition) -> String {
+    match condition {
+        PolicyCondition::NotPlayedForDays { days } => format!("not played for {days} days"),
+        PolicyCondition::EpisodeAddedWithinDays { days } => {
+            format!("new episode within {days} days")
+        }
+        PolicyCondition::AddedWithinDays { days } => format!("added within {days} days"),
+        PolicyCondition::SizeAboveBytes { bytes } => format!("size > {bytes} bytes"),
+        PolicyCondition::SizeBelowBytes { bytes } => format!("size < {bytes} bytes"),
+        PolicyCondition::RatingBelow { rating } => format!("rating < {rating}"),
+        PolicyCondition::RatingAbove { rating } => format!("rating > {rating}"),
+    }
+}
+
+fn days_to_secs(days: u32) -> i64 {
+    i64::from(days) * 86400
+}
+
+pub async fn load_show_facts(db: &DbPool) -> Result<Vec<ShowFacts>, sqlx::Error> {
+    sqlx::query_as::<_, ShowFacts>(
+        r#"
+        SELECT
+            s.id,
+            s.title,
+            s.path,
+            s.location,
+            s.size_bytes,
+            s.first_seen_at,
+            s.episodes_added_at,
+            s.pinned_location,
+            s.rating,
+            a.last_played_at,
+            EXISTS (
+                SELECT 1 FROM jobs j
+                WHERE j.show_id = s.id AND j.status IN ('queued', 'running', 'waiting_for_playback')
+            ) AS has_active_job
+        FROM shows s
+        LEFT JOIN show_activity a ON a.show_id = s.id
+        WHERE s.missing_since IS NULL
+        ORDER BY s.id
+        "#,
+    )
+    .fetch_all(db)
+    .await
+}
+
+pub async fn record_decision(
+    db: &DbPool,
+    decision: &PolicyDecision,
+    action: &str,
+    job_id: Option<i64>,
+    note: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let reason = match note {
+        Some(note) => format!("{} ({note})", decision.reason),
+        None => decision.reason.clone(),
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO policy_decisions (
+            created_at, show_id, show_title, rule_name, source_location,
+            target, action, job_id, reason
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(Utc::now().timestamp())
+    .bind(decision.show_id)
+    .bind(&decision.show_title)
+    .bind(&decision.rule_name)
+    .bind(&decision.source_location)
+    .bind(&decision.target)
+    .bind(action)
+    .bind(job_id)
+    .bind(reason)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Whether the newest logged decision for the same show, rule and target
+/// failed. A show that cannot be moved keeps matching on every pass; this
+/// keeps it from logging the same failure each time.
+pub async fn latest_decision_failed(
+    db: &DbPool,
+    decision: &PolicyDecision,
+) -> Result<bool, sqlx::Error> {
+    let action: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT action FROM policy_decisions
+        WHERE show_id = ? AND rule_name = ? AND target = ?
+        ORDER BY id DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(decision.show_id)
+    .bind(&decision.rule_name)
+    .bind(&decision.target)
+    .fetch_optional(db)
+    .await?;
+    Ok(action.as_deref() == Some(ACTION_FAILED))
+}
+
+pub async fn list_decisions(
+    db: &DbPool,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<PolicyDecisionRecord>, i64), sqlx::Error> {
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM policy_decisions")
+        .fetch_one(db)
+        .await?;
+
+    let records = sqlx::query_as::<_, PolicyDecisionRecord>(
+        r#"
+        SELECT id, created_at, show_id, show_title, rule_name, source_location,
+               target, action, job_id, reason
+        FROM policy_decisions
+        ORDER BY created_at DESC, id DESC
+        LIMIT ? OFFSET ?
+        "#,
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+
+    Ok((records, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_pool;
+
+    const DAY: i64 = 86400;
+
+    fn show(id: i64, location: &str) -> ShowFacts {
+        ShowFacts {
+            id,
+            title: Some(format!("Show {id}")),
+            path: format!("/media/{location}/Show {id}"),
+            location: Some(location.to_string()),
+            size_bytes: Some(1_000),
+            first_seen_at: Some(0),
+            episodes_added_at: None,
+            last_played_at: None,
+            rating: None,
+            pinned_location: None,
+            has_active_job: false,
+        }
+    }
+
+    fn rule(name: &str, target: &str, conditions: Vec<PolicyCondition>) -> PolicyRule {
+        PolicyRule {
+            name: name.to_string(),
+            enabled: true,
+            target: target.to_string(),
+            conditions,
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins_and_skips_shows_already_in_target() {
+        let now = 100 * DAY;
+        let mut fresh = show(1, "cold");
+        fresh.episodes_added_at = Some(now - DAY);
+        let mut stale = show(2, "hot");
+        stale.last_played_at = Some(now - 90 * DAY);
+        let already_cold = show(3, "cold");
+
+        let rules = vec![
+            rule(
+                "new episodes",
+                "hot",
+                vec![PolicyCondition::EpisodeAddedWithinDays { days: 7 }],
+            ),
+            rule(
+                "unwatched",
+                "cold",
+                vec![PolicyCondition::NotPlayedForDays { days: 60 }],
+            ),
+        ];
+
+        let decisions = evaluate(&rules, &[fresh, stale, already_cold], now);
+        let summary: Vec<_> = decisions
+            .iter()
+            .map(|d| (d.show_id, d.rule_name.as_str(), d.target.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(1, "new episodes", "hot"), (2, "unwatched", "cold")]
+        );
+    }
+
+    #[test]
+    fn unknown_inputs_and_active_jobs_do_not_match() {
+        let mut busy = show(1, "hot");
+        busy.has_active_job = true;
+        busy.rating = Some(3.0);
+        let unrated = show(2, "hot");
+
+        let rules = vec![rule(
+            "low rated",
+            "cold",
+            vec![PolicyCondition::RatingBelow { rating: 6.0 }],
+        )];
+
+        assert!(evaluate(&rules, &[busy, unrated], 0).is_empty());
+    }
+
+    #[test]
+    fn simulation_projects_usage_after_each_move() {
+        let mut demote = show(1, "hot");
+        demote.size_bytes = Some(300);
+        let mut promote = show(2, "cold");
+        promote.size_bytes = Some(100);
+        promote.rating = Some(9.0);
+
+        let rules = vec![
+            rule(
+                "favourites",
+                "hot",
+                vec![PolicyCondition::RatingAbove { rating: 8.0 }],
+            ),
+            rule(
+                "everything else",
+                "cold",
+                vec![PolicyCondition::AddedWithinDays { days: 1 }],
+            ),
+        ];
+        let decisions = evaluate(&rules, &[demote, promote], 0);
+        let pool = |used_bytes| ProjectedUsage {
+            total_bytes: 1_000,
+            used_bytes,
+        };
+
+        let tier = |name: &str, usage| TierProjection {
+            tier: name.to_string(),
+            usage,
+        };
+
+        let simulation = simulate(
+            decisions,
+            2,
+            1,
+            &[],
+            vec![tier("hot", Some(pool(500))), tier("cold", None)],
+        );
+
+        assert_eq!(simulation.next_run_count, 1);
+        assert_eq!(simulation.bytes_demoted, 300);
+        assert_eq!(simulation.bytes_promoted, 100);
+        let hot_steps: Vec<_> = simulation
+            .moves
+            .iter()
+            .map(|step| {
+                let hot = if step.decision.target == "hot" {
+                    step.target_after
+                } else {
+                    step.source_after
+                };
+                hot.map(|usage| usage.used_bytes)
+            })
+            .collect();
+        assert_eq!(hot_steps, vec![Some(200), Some(300)]);
+        assert!(simulation.after[1].usage.is_none());
+        assert!(simulation.refused.is_empty());
+    }
+
+    #[test]
+    fn simulation_refuses_moves_that_break_tier_limits() {
+        let shows: Vec<_> = (1..=3)
+            .map(|id| {
+                let mut show = show(id, "cold");
+                show.size_bytes = Some(200);
+                show
+            })
+            .collect();
+        let rules = vec![rule(
+            "everything",
+            "hot",
+            vec![PolicyCondition::AddedWithinDays { days: 1 }],
+        )];
+        let decisions = evaluate(&rules, &shows, 0);
+        let hot = TierConfig {
+            name: "hot".to_string(),
+            limits: TierLimits {
+                max_used_percent: Some(80.0),
+                min_free_bytes: None,
+            },
+            ..TierConfig::default()
+        };
+        let before = vec![TierProjection {
+            tier: "hot".to_string(),
+            usage: Some(ProjectedUsage {
+                total_bytes: 1_000,
+                used_bytes: 300,
+            }),
+        }];
+
+        let simulation = simulate(decisions, 3, 3, &[hot], before);
+
+        let planned: Vec<_> = simulation
+            .moves
+            .iter()
+            .map(|m| m.decision.show_id)
+            .collect();
+        assert_eq!(planned, vec![1, 2]);
+        let refused: Vec<_> = simulation
+            .refused
+            .iter()
+            .map(|m| (m.decision.show_id, m.reason.as_str()))
+            .collect();
+        assert_eq!(
+            refused,
+            vec![(3, "Move would exceed the limits of tier hot")]
+        );
+        assert_eq!(simulation.next_run_count, 2);
+        assert_eq!(simulation.after[0].usage.unwrap().used_bytes, 700);
+    }
+
+    #[tokio::test]
+    async fn repeated_failures_are_logged_once() {
+        let (_dir, db) = test_pool().await;
+        sqlx::query(
+            "INSERT INTO shows (id, title, path, location, size_bytes, first_seen_at) \
+             VALUES (1, 'One', '/media/cold/One', 'cold', 100, ?)",
+        )
+        .bind(Utc::now().timestamp())
+        .execute(&db)
+        .await
+        .unwrap();
+        // `hot` has no roots, so every attempt to queue the move fails.
+        let tier = |name: &str| TierConfig {
+            name: name.to_string(),
+            ..TierConfig::default()
+        };
+        let mut config = Config {
+            tiers: vec![tier("hot"), tier("cold")],
+            ..Config::default()
+        };
+        config.policies.rules = vec![rule(
+            "new shows",
+            "hot",
+            vec![PolicyCondition::AddedWithinDays { days: 1 }],
+        )];
+
+        for _ in 0..3 {
+            let summary = run_once(&db, &config).await.unwrap();
+            assert_eq!(summary.failed, 1);
+        }
+
+        let (records, total) = list_decisions(&db, 10, 0).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(records[0].action, ACTION_FAILED);
+    }
+}
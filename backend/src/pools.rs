@@ -3,6 +3,11 @@ use serde::Serialize;
 use std::path::Path;
 use tracing::warn;
 
+use crate::{
+    config::{StorageClass, TierConfig},
+    quotas::QuotaUsage,
+};
+
 #[derive(Debug, Serialize)]
 pub struct PoolSummary {
     pub path: String,
@@ -11,13 +16,88 @@ pub struct PoolSummary {
     pub free_bytes: u64,
 }
 
+#[derive(Debug, Serialize)]
+pub struct TierSummary {
+    pub name: String,
+    pub class: StorageClass,
+    /// Totals across the tier's roots; `None` when none can be inspected.
+    pub usage: Option<PoolSummary>,
+    /// Each readable root on its own.
+    pub roots: Vec<PoolSummary>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct PoolsResponse {
-    pub hot: Option<PoolSummary>,
-    pub cold: Option<PoolSummary>,
+    /// Every configured tier, fastest first.
+    pub tiers: Vec<TierSummary>,
+    /// Per-library tier usage against `Config::library_quotas`.
+    pub quotas: Vec<QuotaUsage>,
 }
 
 pub fn collect_pool_usage(root: &str) -> Option<PoolSummary> {
+    read_pool(root).map(|(_, summary)| summary)
+}
+
+/// Usage summed over a tier's roots. Roots sharing a filesystem are counted
+/// once, and `path` lists the roots. Unreadable roots are left out.
+fn collect_tier_usage(tier: &TierConfig) -> Option<PoolSummary> {
+    let mut filesystems = Vec::new();
+    let mut total: Option<PoolSummary> = None;
+
+    for root in &tier.roots {
+        let Some((filesystem, pool)) = read_pool(root) else {
+            continue;
+        };
+        let counted = filesystems.contains(&filesystem);
+        filesystems.push(filesystem);
+
+        total = Some(match total {
+            None => pool,
+            Some(sum) => PoolSummary {
+                path: format!("{}, {}", sum.path, pool.path),
+                total_bytes: sum.total_bytes + if counted { 0 } else { pool.total_bytes },
+                used_bytes: sum.used_bytes + if counted { 0 } else { pool.used_bytes },
+                free_bytes: sum.free_bytes + if counted { 0 } else { pool.free_bytes },
+            },
+        });
+    }
+
+    total
+}
+
+/// `collect_tier_usage` on the blocking pool, so a hung mount cannot stall
+/// the async runtime.
+pub async fn tier_usage(tier: &TierConfig) -> Option<PoolSummary> {
+    let tier = tier.clone();
+    tokio::task::spawn_blocking(move || collect_tier_usage(&tier))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Summaries of every tier, read on the blocking pool.
+pub async fn tier_summaries(tiers: &[TierConfig]) -> Vec<TierSummary> {
+    let tiers = tiers.to_vec();
+    tokio::task::spawn_blocking(move || {
+        tiers
+            .iter()
+            .map(|tier| TierSummary {
+                name: tier.name.clone(),
+                class: tier.class,
+                usage: collect_tier_usage(tier),
+                roots: tier
+                    .roots
+                    .iter()
+                    .filter_map(|root| collect_pool_usage(root))
+                    .collect(),
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+fn read_pool(root: &str) -> Option<(u64, PoolSummary)> {
     let trimmed = root.trim();
     if trimmed.is_empty() {
         return None;
@@ -52,10 +132,13 @@ pub fn collect_pool_usage(root: &str) -> Option<PoolSummary> {
     let free = free_blocks.saturating_mul(block_size);
     let used = total.saturating_sub(free);
 
-    Some(PoolSummary {
-        path: trimmed.to_string(),
-        total_bytes: total,
-        used_bytes: used,
-        free_bytes: free,
-    })
+    Some((
+        stats.filesystem_id() as u64,
+        PoolSummary {
+            path: trimmed.to_string(),
+            total_bytes: total,
+            used_bytes: used,
+            free_bytes: free,
+        },
+    ))
 }
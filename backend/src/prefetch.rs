@@ -0,0 +1,445 @@
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, hash_map::Entry},
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    sync::RwLock,
+    task::JoinHandle,
+    time::{Duration, sleep},
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{self, Config},
+    db::DbPool,
+    jellyfin::{JellyfinClient, JellyfinError},
+    jobs::{self, JobError, KIND_PREFETCH, KIND_PREFETCH_RETURN, TransferEnd},
+    playback,
+    scanner::{self, EpisodeFile, ScanStatus},
+    tiers,
+};
+
+#[derive(Debug)]
+pub enum PrefetchError {
+    Jellyfin(JellyfinError),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for PrefetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrefetchError::Jellyfin(err) => write!(f, "Jellyfin error: {err}"),
+            PrefetchError::Database(err) => write!(f, "Database error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for PrefetchError {}
+
+impl From<JellyfinError> for PrefetchError {
+    fn from(value: JellyfinError) -> Self {
+        PrefetchError::Jellyfin(value)
+    }
+}
+
+impl From<sqlx::Error> for PrefetchError {
+    fn from(value: sqlx::Error) -> Self {
+        PrefetchError::Database(value)
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PrefetchRunSummary {
+    /// "Next Up" episodes that belong to a show outside the fastest tier.
+    pub next_up: usize,
+    pub queued_prefetches: usize,
+    pub queued_returns: usize,
+    /// Bytes of prefetched episodes the plan keeps in the fastest tier.
+    pub planned_bytes: i64,
+    pub budget_bytes: i64,
+}
+
+/// An episode someone is about to watch, with every file that belongs to it.
+#[derive(Debug, Clone)]
+pub struct WantedEpisode {
+    pub show_id: i64,
+    /// The video followed by its companion files, relative to the show folder.
+    pub files: Vec<String>,
+    pub size_bytes: i64,
+}
+
+/// Files of one show that a single prefetch or return job should move.
+#[derive(Debug, Default, PartialEq)]
+pub struct FileBatch {
+    pub files: Vec<String>,
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct PrefetchPlan {
+    pub prefetch: BTreeMap<i64, FileBatch>,
+    pub returns: BTreeMap<i64, FileBatch>,
+    pub planned_bytes: i64,
+}
+
+/// A show outside the fastest tier.
+#[derive(Debug, sqlx::FromRow)]
+struct ColdShow {
+    id: i64,
+    path: String,
+    location: String,
+    has_active_job: bool,
+    /// Pinned to a tier other than the fastest; its prefetched files are only returned.
+    pinned_elsewhere: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PrefetchedFile {
+    show_id: i64,
+    relative_path: String,
+    size_bytes: i64,
+}
+
+/// Start the background task that copies upcoming episodes of slower-tier shows to the fastest tier.
+pub fn start_prefetcher(
+    db: DbPool,
+    config: Arc<RwLock<Config>>,
+    scan_status: Arc<RwLock<ScanStatus>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Starting prefetch background task");
+
+        loop {
+            let interval_minutes = config.read().await.prefetch.interval_minutes.max(1);
+            sleep(Duration::from_secs(u64::from(interval_minutes) * 60)).await;
+
+            let config_snapshot = config.read().await.clone();
+            if !config_snapshot.prefetch.enabled || !config::config_is_ready(&config_snapshot) {
+                continue;
+            }
+            if scan_status.read().await.is_running() {
+                info!("Skipping prefetch while a scan is running");
+                continue;
+            }
+
+            match run_once(&db, &config_snapshot).await {
+                Ok(summary) => info!(
+                    next_up = summary.next_up,
+                    queued_prefetches = summary.queued_prefetches,
+                    queued_returns = summary.queued_returns,
+                    planned_bytes = summary.planned_bytes,
+                    "Prefetch run finished"
+                ),
+                Err(PrefetchError::Jellyfin(JellyfinError::NotConfigured)) => {}
+                Err(err) => error!(%err, "Prefetch run failed"),
+            }
+        }
+    })
+}
+
+/// Looks up every user's "Next Up" episodes and queues jobs that bring the
+/// next `episodes_ahead` episodes of shows in slower tiers into the fastest
+/// tier, and send prefetched episodes nobody is about to watch back to the
+/// show's own tier.
+pub async fn run_once(db: &DbPool, config: &Config) -> Result<PrefetchRunSummary, PrefetchError> {
+    let client = JellyfinClient::from_config(config)?;
+    let Some(fastest) = config.fastest_tier() else {
+        return Ok(PrefetchRunSummary::default());
+    };
+    let episodes_ahead = config.prefetch.episodes_ahead.max(1) as usize;
+
+    let shows = sqlx::query_as::<_, ColdShow>(
+        r#"
+        SELECT s.id, s.path, s.location,
+               EXISTS(
+                   SELECT 1 FROM jobs j
+                   WHERE j.show_id = s.id AND j.status IN ('queued', 'running', 'waiting_for_playback')
+               ) AS has_active_job,
+               (s.pinned_location IS NOT NULL AND s.pinned_location <> ?) AS pinned_elsewhere
+        FROM shows s
+        WHERE s.location IS NOT NULL AND s.location <> ? AND s.missing_since IS NULL
+        "#,
+    )
+    .bind(&fastest.name)
+    .bind(&fastest.name)
+    .fetch_all(db)
+    .await?;
+    let prefetched = sqlx::query_as::<_, PrefetchedFile>(
+        "SELECT show_id, relative_path, size_bytes FROM prefetched_files",
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut summary = PrefetchRunSummary {
+        budget_bytes: config.prefetch.hot_budget_bytes.min(i64::MAX as u64) as i64,
+        ..PrefetchRunSummary::default()
+    };
+
+    let mut next_up = Vec::new();
+    for user in client.list_users().await? {
+        for item in client.next_up(&user.id).await? {
+            let (Some(path), Some(season), Some(episode)) =
+                (item.path, item.parent_index_number, item.index_number)
+            else {
+                continue;
+            };
+            if let Some(show) = shows
+                .iter()
+                .filter(|show| !show.pinned_elsewhere)
+                .find(|show| playback::item_belongs_to_show(&path, &show.path))
+            {
+                next_up.push((show, season, episode));
+            }
+        }
+    }
+    summary.next_up = next_up.len();
+
+    let mut indexes: HashMap<i64, Vec<EpisodeFile>> = HashMap::new();
+    let mut wanted = Vec::new();
+    for (show, season, episode) in next_up {
+        if let Entry::Vacant(slot) = indexes.entry(show.id) {
+            let Some(hot_dir) = hot_folder(db, config, &show.path).await? else {
+                continue;
+            };
+            let cold_dir = PathBuf::from(&show.path);
+            let index = tokio::task::spawn_blocking(move || {
+                // Prefetched episodes are missing from the show's folder but sit in the fastest tier.
+                let mut episodes = scanner::index_episodes(&cold_dir);
+                episodes.extend(scanner::index_episodes(&hot_dir));
+                episodes.sort_by(|a, b| {
+                    (a.season, a.episode, &a.relative_path).cmp(&(
+                        b.season,
+                        b.episode,
+                        &b.relative_path,
+                    ))
+                });
+                episodes.dedup_by(|a, b| a.relative_path == b.relative_path);
+                episodes
+            })
+            .await
+            .unwrap_or_default();
+            slot.insert(index);
+        }
+
+        wanted.extend(
+            indexes[&show.id]
+                .iter()
+                .filter(|file| (file.season, file.episode) >= (season, episode))
+                .take(episodes_ahead)
+                .map(|file| WantedEpisode {
+                    show_id: show.id,
+                    files: std::iter::once(file.relative_path.clone())
+                        .chain(file.companion_paths.iter().cloned())
+                        .collect(),
+                    size_bytes: file.size_bytes,
+                }),
+        );
+    }
+
+    let prefetched_sizes: HashMap<(i64, String), i64> = prefetched
+        .into_iter()
+        .map(|file| ((file.show_id, file.relative_path), file.size_bytes))
+        .collect();
+    let busy: HashSet<i64> = shows
+        .iter()
+        .filter(|show| show.has_active_job)
+        .map(|show| show.id)
+        .collect();
+    let plan = plan(&wanted, &prefetched_sizes, &busy, summary.budget_bytes);
+    summary.planned_bytes = plan.planned_bytes;
+
+    for show in &shows {
+        // A show can only have one active job, so returns (which free budget) go
+        // first and its prefetches follow on the next run.
+        let (kind, batch) = if let Some(batch) = plan.returns.get(&show.id) {
+            (KIND_PREFETCH_RETURN, batch)
+        } else if let Some(batch) = plan.prefetch.get(&show.id) {
+            (KIND_PREFETCH, batch)
+        } else {
+            continue;
+        };
+
+        let Some(hot_dir) = hot_folder(db, config, &show.path).await? else {
+            continue;
+        };
+        let cold_dir = PathBuf::from(&show.path);
+        let hot = TransferEnd {
+            tier: &fastest.name,
+            dir: &hot_dir,
+        };
+        let cold = TransferEnd {
+            tier: &show.location,
+            dir: &cold_dir,
+        };
+        let (source, destination) = if kind == KIND_PREFETCH {
+            (cold, hot)
+        } else {
+            (hot, cold)
+        };
+
+        match jobs::create_prefetch_job(
+            db,
+            config,
+            show.id,
+            kind,
+            source,
+            destination,
+            &batch.files,
+            batch.size_bytes,
+        )
+        .await
+        {
+            Ok(_) if kind == KIND_PREFETCH => summary.queued_prefetches += 1,
+            Ok(_) => summary.queued_returns += 1,
+            Err(JobError::Database(err)) => return Err(err.into()),
+            Err(err) => warn!(show_id = show.id, ?err, "Could not queue prefetch job"),
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Keeps wanted episodes, in priority order, while they fit the byte budget.
+///
+/// Kept files not yet in the fastest tier are prefetched and prefetched files no
+/// longer kept are returned. Shows with an active job are left alone, but their
+/// prefetched files still count against the budget.
+pub fn plan(
+    wanted: &[WantedEpisode],
+    prefetched: &HashMap<(i64, String), i64>,
+    busy_shows: &HashSet<i64>,
+    budget_bytes: i64,
+) -> PrefetchPlan {
+    let mut result = PrefetchPlan {
+        planned_bytes: prefetched
+            .iter()
+            .filter(|((show_id, _), _)| busy_shows.contains(show_id))
+            .map(|(_, size)| *size)
+            .sum(),
+        ..PrefetchPlan::default()
+    };
+    let mut kept: HashSet<(i64, &str)> = HashSet::new();
+
+    for episode in wanted {
+        let Some(video) = episode.files.first() else {
+            continue;
+        };
+        if busy_shows.contains(&episode.show_id)
+            || kept.contains(&(episode.show_id, video.as_str()))
+            || result.planned_bytes + episode.size_bytes > budget_bytes
+        {
+            continue;
+        }
+
+        result.planned_bytes += episode.size_bytes;
+        kept.extend(
+            episode
+                .files
+                .iter()
+                .map(|file| (episode.show_id, file.as_str())),
+        );
+        let missing: Vec<String> = episode
+            .files
+            .iter()
+            .filter(|file| !prefetched.contains_key(&(episode.show_id, (*file).clone())))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            let batch = result.prefetch.entry(episode.show_id).or_default();
+            batch.files.extend(missing);
+            batch.size_bytes += episode.size_bytes;
+        }
+    }
+
+    for ((show_id, file), size) in prefetched {
+        if !busy_shows.contains(show_id) && !kept.contains(&(*show_id, file.as_str())) {
+            let batch = result.returns.entry(*show_id).or_default();
+            batch.files.push(file.clone());
+            batch.size_bytes += size;
+        }
+    }
+    for batch in result.returns.values_mut() {
+        batch.files.sort();
+    }
+
+    result
+}
+
+/// Where a show's prefetched files live: the folder a move to the fastest
+/// tier would use.
+async fn hot_folder(
+    db: &DbPool,
+    config: &Config,
+    show_path: &str,
+) -> Result<Option<PathBuf>, sqlx::Error> {
+    let show_path = Path::new(show_path);
+    let (Some(fastest), Some((_, root))) = (config.fastest_tier(), config.locate(show_path)) else {
+        return Ok(None);
+    };
+    let Ok(relative) = show_path.strip_prefix(root) else {
+        return Ok(None);
+    };
+    let fastest_root = tiers::choose_root(db, fastest, relative).await?;
+    Ok(fastest_root.map(|fastest_root| fastest_root.join(relative)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episode(show_id: i64, video: &str, size_bytes: i64) -> WantedEpisode {
+        WantedEpisode {
+            show_id,
+            files: vec![video.to_string()],
+            size_bytes,
+        }
+    }
+
+    #[test]
+    fn plans_within_budget_and_returns_unwanted_files() {
+        let wanted = vec![
+            episode(1, "S01E02.mkv", 40),
+            episode(1, "S01E03.mkv", 40),
+            episode(2, "S02E01.mkv", 30),
+            episode(1, "S01E04.mkv", 40),
+            episode(3, "S01E01.mkv", 10),
+            episode(1, "S01E02.mkv", 40),
+        ];
+        let prefetched = HashMap::from([
+            ((1, "S01E02.mkv".to_string()), 40),
+            ((1, "S01E01.mkv".to_string()), 40),
+            ((3, "S01E09.mkv".to_string()), 15),
+        ]);
+        let busy = HashSet::from([3]);
+
+        let result = plan(&wanted, &prefetched, &busy, 130);
+
+        assert_eq!(
+            result.prefetch.get(&1),
+            Some(&FileBatch {
+                files: vec!["S01E03.mkv".to_string()],
+                size_bytes: 40,
+            })
+        );
+        assert_eq!(
+            result.prefetch.get(&2),
+            Some(&FileBatch {
+                files: vec!["S02E01.mkv".to_string()],
+                size_bytes: 30,
+            })
+        );
+        assert!(!result.prefetch.contains_key(&3));
+        assert_eq!(
+            result.returns.get(&1),
+            Some(&FileBatch {
+                files: vec!["S01E01.mkv".to_string()],
+                size_bytes: 40,
+            })
+        );
+        assert!(!result.returns.contains_key(&3));
+        assert_eq!(result.planned_bytes, 125);
+    }
+}
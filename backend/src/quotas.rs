@@ -0,0 +1,309 @@
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use tracing::warn;
+
+use crate::{
+    config::{Config, LibraryQuota},
+    db::DbPool,
+    pools,
+};
+
+/// How much of a tier a library uses against its quota.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaUsage {
+    pub library: String,
+    pub tier: String,
+    pub max_bytes: Option<u64>,
+    pub max_percent: Option<f64>,
+    /// The effective limit; `None` when a percentage quota cannot be resolved
+    /// because the tier's capacity is unreadable.
+    pub limit_bytes: Option<u64>,
+    /// Bytes of the library's shows in the tier plus moves into it still in flight.
+    pub used_bytes: u64,
+}
+
+/// A move into a tier that would push a library over its quota there.
+#[derive(Debug, Clone)]
+pub struct QuotaExceeded {
+    pub library: String,
+    pub tier: String,
+    pub limit_bytes: u64,
+    pub used_bytes: u64,
+    pub requested_bytes: u64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PendingMove {
+    source_path: String,
+    source_tier: Option<String>,
+    target_tier: Option<String>,
+    total_bytes: Option<i64>,
+}
+
+/// Per-tier usage of each quota'd library, updated as moves are planned.
+#[derive(Debug)]
+pub struct QuotaTracker {
+    roots: Vec<PathBuf>,
+    /// Each quota with its library path relative to the tier roots.
+    quotas: Vec<(PathBuf, QuotaUsage)>,
+}
+
+impl QuotaTracker {
+    /// `tier_totals` maps tier names to their capacity, for percentage quotas.
+    pub fn new(config: &Config, tier_totals: &HashMap<String, u64>) -> Self {
+        let roots = config.all_roots();
+        let quotas = config
+            .library_quotas
+            .iter()
+            .filter_map(|quota| {
+                let tier = match quota.tier.as_deref() {
+                    Some(name) => config.tier(name)?,
+                    None => config.fastest_tier()?,
+                };
+                let key = relative_to_tiers(Path::new(quota.library.trim()), &roots)?;
+                let usage = usage_for(quota, &tier.name, tier_totals.get(&tier.name).copied());
+                Some((key.to_path_buf(), usage))
+            })
+            .collect();
+
+        Self { roots, quotas }
+    }
+
+    /// Builds a tracker from the shows currently in each tier and the episodes
+    /// prefetched into the fastest one, adjusted for jobs still on their way in
+    /// or out.
+    pub async fn load(db: &DbPool, config: &Config) -> Result<Self, sqlx::Error> {
+        let mut tier_totals = HashMap::new();
+        for quota in config
+            .library_quotas
+            .iter()
+            .filter(|quota| quota.max_percent.is_some())
+        {
+            let tier = match quota.tier.as_deref() {
+                Some(name) => config.tier(name),
+                None => config.fastest_tier(),
+            };
+            let Some(tier) = tier else {
+                continue;
+            };
+            if !tier_totals.contains_key(&tier.name)
+                && let Some(pool) = pools::tier_usage(tier).await
+            {
+                tier_totals.insert(tier.name.clone(), pool.total_bytes);
+            }
+        }
+        let mut tracker = Self::new(config, &tier_totals);
+        if tracker.quotas.is_empty() {
+            return Ok(tracker);
+        }
+
+        let shows: Vec<(String, Option<i64>, String)> = sqlx::query_as(
+            "SELECT path, size_bytes, location FROM shows \
+             WHERE location IS NOT NULL AND missing_since IS NULL",
+        )
+        .fetch_all(db)
+        .await?;
+        let pending = sqlx::query_as::<_, PendingMove>(
+            r#"
+            SELECT source_path, source_tier, target_tier, total_bytes
+            FROM jobs
+            WHERE status IN ('queued', 'running', 'waiting_for_playback')
+            "#,
+        )
+        .fetch_all(db)
+        .await?;
+        let prefetched: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT hot_path, COALESCE(SUM(size_bytes), 0) FROM prefetched_files GROUP BY hot_path",
+        )
+        .fetch_all(db)
+        .await?;
+
+        for (path, size_bytes, location) in shows {
+            tracker.add(&path, &location, size_bytes.unwrap_or(0));
+        }
+        if let Some(fastest) = config.fastest_tier() {
+            for (hot_path, size_bytes) in prefetched {
+                tracker.add(&hot_path, &fastest.name, size_bytes);
+            }
+        }
+        for job in pending {
+            let total_bytes = job.total_bytes.unwrap_or(0);
+            if let Some(target_tier) = &job.target_tier {
+                tracker.add(&job.source_path, target_tier, total_bytes);
+            }
+            if let Some(source_tier) = &job.source_tier {
+                tracker.add(&job.source_path, source_tier, -total_bytes);
+            }
+        }
+
+        Ok(tracker)
+    }
+
+    /// Whether `size_bytes` more of the show at `show_path` fit in `tier`.
+    pub fn check(&self, show_path: &str, tier: &str, size_bytes: i64) -> Result<(), QuotaExceeded> {
+        let Some(usage) = self
+            .find(show_path, tier)
+            .map(|index| &self.quotas[index].1)
+        else {
+            return Ok(());
+        };
+        let Some(limit_bytes) = usage.limit_bytes else {
+            return Ok(());
+        };
+
+        let requested_bytes = size_bytes.max(0) as u64;
+        if usage.used_bytes.saturating_add(requested_bytes) > limit_bytes {
+            return Err(QuotaExceeded {
+                library: usage.library.clone(),
+                tier: usage.tier.clone(),
+                limit_bytes,
+                used_bytes: usage.used_bytes,
+                requested_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Records `delta` bytes arriving in (positive) or leaving (negative) `tier`.
+    pub fn add(&mut self, show_path: &str, tier: &str, delta: i64) {
+        if let Some(index) = self.find(show_path, tier) {
+            let usage = &mut self.quotas[index].1;
+            usage.used_bytes = usage.used_bytes.saturating_add_signed(delta);
+        }
+    }
+
+    pub fn usage(&self) -> Vec<QuotaUsage> {
+        self.quotas.iter().map(|(_, usage)| usage.clone()).collect()
+    }
+
+    /// The quota in `tier` of the most specific library containing `show_path`.
+    fn find(&self, show_path: &str, tier: &str) -> Option<usize> {
+        let relative = relative_to_tiers(Path::new(show_path), &self.roots)?;
+        self.quotas
+            .iter()
+            .enumerate()
+            .filter(|(_, (key, usage))| usage.tier == tier && relative.starts_with(key))
+            .max_by_key(|(_, (key, _))| key.components().count())
+            .map(|(index, _)| index)
+    }
+}
+
+fn usage_for(quota: &LibraryQuota, tier: &str, tier_total_bytes: Option<u64>) -> QuotaUsage {
+    let limit_bytes = quota.max_bytes.or_else(|| {
+        let percent = quota.max_percent?;
+        let Some(total) = tier_total_bytes else {
+            warn!(
+                library = %quota.library,
+                tier,
+                "Tier capacity unavailable; percentage quota not enforced"
+            );
+            return None;
+        };
+        Some((total as f64 * percent / 100.0) as u64)
+    });
+
+    QuotaUsage {
+        library: quota.library.trim().to_string(),
+        tier: tier.to_string(),
+        max_bytes: quota.max_bytes,
+        max_percent: quota.max_percent,
+        limit_bytes,
+        used_bytes: 0,
+    }
+}
+
+fn relative_to_tiers<'a>(path: &'a Path, roots: &[PathBuf]) -> Option<&'a Path> {
+    roots.iter().find_map(|root| path.strip_prefix(root).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TierConfig;
+
+    fn tier(name: &str, roots: &[&str]) -> TierConfig {
+        TierConfig {
+            name: name.to_string(),
+            roots: roots.iter().map(|root| root.to_string()).collect(),
+            ..TierConfig::default()
+        }
+    }
+
+    #[test]
+    fn tracks_libraries_across_tiers() {
+        let config = Config {
+            tiers: vec![
+                tier("hot", &["/media/hot"]),
+                tier("warm", &["/media/warm"]),
+                tier("cold", &["/media/cold", "/media/cold2"]),
+            ],
+            library_paths: vec!["/media/hot/anime".to_string(), "/media/cold/tv".to_string()],
+            library_quotas: vec![
+                LibraryQuota {
+                    library: "/media/hot/anime".to_string(),
+                    tier: None,
+                    max_bytes: Some(100),
+                    max_percent: None,
+                },
+                LibraryQuota {
+                    library: "/media/cold/tv".to_string(),
+                    tier: Some("warm".to_string()),
+                    max_bytes: None,
+                    max_percent: Some(10.0),
+                },
+            ],
+            ..Config::default()
+        };
+        let totals = HashMap::from([("warm".to_string(), 1_000)]);
+        let mut tracker = QuotaTracker::new(&config, &totals);
+
+        tracker.add("/media/hot/anime/Frieren", "hot", 60);
+        assert!(
+            tracker
+                .check("/media/cold/anime/Mushishi", "hot", 40)
+                .is_ok()
+        );
+        let exceeded = tracker
+            .check("/media/cold/anime/Mushishi", "hot", 41)
+            .unwrap_err();
+        assert_eq!(exceeded.library, "/media/hot/anime");
+        assert_eq!(exceeded.used_bytes, 60);
+        assert!(
+            tracker
+                .check("/media/cold/anime/Mushishi", "warm", 10_000)
+                .is_ok()
+        );
+
+        assert!(
+            tracker
+                .check("/media/cold/tv/Severance", "warm", 101)
+                .is_err()
+        );
+        assert!(
+            tracker
+                .check("/media/cold/tv/Severance", "hot", 101)
+                .is_ok()
+        );
+        tracker.add("/media/warm/tv/Andor", "warm", 100);
+        assert!(
+            tracker
+                .check("/media/cold2/tv/Severance", "warm", 1)
+                .is_err()
+        );
+        assert!(
+            tracker
+                .check("/media/cold/movies/Heat", "hot", 10_000)
+                .is_ok()
+        );
+
+        tracker.add("/media/hot/anime/Frieren", "hot", -60);
+        assert!(
+            tracker
+                .check("/media/cold/anime/Mushishi", "hot", 100)
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use serde::Serialize;
+use sqlx::FromRow;
+
+use crate::{
+    db::DbPool,
+    scanner::{ScanError, ScanSummary},
+};
+
+const STATUS_SUCCESS: &str = "success";
+const STATUS_FAILED: &str = "failed";
+const STATUS_CANCELLED: &str = "cancelled";
+
+/// A finished filesystem scan, as listed by `GET /api/scan/runs`.
+#[derive(Debug, Serialize)]
+pub struct ScanRun {
+    pub id: i64,
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub full: bool,
+    /// `success`, `failed` or `cancelled`.
+    pub status: String,
+    pub scanned_libraries: i64,
+    pub shows_processed: i64,
+    pub inserted: i64,
+    pub updated: i64,
+    pub unchanged: i64,
+    pub removed: i64,
+    pub purged: i64,
+    /// Why the scan failed.
+    pub error: Option<String>,
+    /// Libraries and show folders that could not be read.
+    pub warnings: Vec<String>,
+}
+
+#[derive(FromRow)]
+struct ScanRunRow {
+    id: i64,
+    started_at: i64,
+    finished_at: i64,
+    full: bool,
+    status: String,
+    scanned_libraries: i64,
+    shows_processed: i64,
+    inserted: i64,
+    updated: i64,
+    unchanged: i64,
+    removed: i64,
+    purged: i64,
+    error: Option<String>,
+    warnings: Option<String>,
+}
+
+impl From<ScanRunRow> for ScanRun {
+    fn from(row: ScanRunRow) -> Self {
+        Self {
+            id: row.id,
+            started_at: row.started_at,
+            finished_at: row.finished_at,
+            full: row.full,
+            status: row.status,
+            scanned_libraries: row.scanned_libraries,
+            shows_processed: row.shows_processed,
+            inserted: row.inserted,
+            updated: row.updated,
+            unchanged: row.unchanged,
+            removed: row.removed,
+            purged: row.purged,
+            error: row.error,
+            warnings: row
+                .warnings
+                .and_then(|raw| serde_json::from_str(&raw).ok())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Stores the outcome of a scan that started at `started_at`.
+pub async fn record(
+    db: &DbPool,
+    started_at: i64,
+    finished_at: i64,
+    full: bool,
+    result: &Result<ScanSummary, ScanError>,
+) -> Result<(), sqlx::Error> {
+    let empty = ScanSummary::default();
+    let (status, summary, error) = match result {
+        Ok(summary) if summary.cancelled => (STATUS_CANCELLED, summary, None),
+        Ok(summary) => (STATUS_SUCCESS, summary, None),
+        Err(err) => (STATUS_FAILED, &empty, Some(err.to_string())),
+    };
+    let warnings = (!summary.warnings.is_empty())
+        .then(|| serde_json::to_string(&summary.warnings).ok())
+        .flatten();
+
+    sqlx::query(
+        r#"
+        INSERT INTO scan_runs (
+            started_at, finished_at, full, status, scanned_libraries, shows_processed,
+            inserted, updated, unchanged, removed, purged, error, warnings
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(started_at)
+    .bind(finished_at)
+    .bind(full)
+    .bind(status)
+    .bind(summary.scanned_libraries as i64)
+    .bind(summary.shows_processed as i64)
+    .bind(summary.inserted as i64)
+    .bind(summary.updated as i64)
+    .bind(summary.unchanged as i64)
+    .bind(summary.removed as i64)
+    .bind(summary.purged as i64)
+    .bind(error)
+    .bind(warnings)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Lists past scans, newest first, with the total count.
+pub async fn list(
+    db: &DbPool,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<ScanRun>, i64), sqlx::Error> {
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM scan_runs")
+        .fetch_one(db)
+        .await?;
+
+    let rows = sqlx::query_as::<_, ScanRunRow>(
+        r#"
+        SELECT id, started_at, finished_at, full, status, scanned_libraries, shows_processed,
+               inserted, updated, unchanged, removed, purged, error, warnings
+        FROM scan_runs
+        ORDER BY started_at DESC, id DESC
+        LIMIT ? OFFSET ?
+        "#,
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+
+    Ok((rows.into_iter().map(ScanRun::from).collect(), total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_pool;
+
+    #[tokio::test]
+    async fn records_and_lists_scan_outcomes() {
+        let (_dir, db) = test_pool().await;
+        let finished = ScanSummary {
+            scanned_libraries: 2,
+            shows_processed: 5,
+            inserted: 1,
+            warnings: vec!["/media/tv: library path does not exist".to_string()],
+            ..ScanSummary::default()
+        };
+        let cancelled = ScanSummary {
+            shows_processed: 3,
+            cancelled: true,
+            ..ScanSummary::default()
+        };
+        let failed = Err(ScanError::Database(sqlx::Error::RowNotFound));
+
+        record(&db, 100, 110, true, &Ok(finished)).await.unwrap();
+        record(&db, 200, 210, false, &Ok(cancelled)).await.unwrap();
+        record(&db, 300, 310, false, &failed).await.unwrap();
+
+        let (runs, total) = list(&db, 10, 0).await.unwrap();
+        assert_eq!(total, 3);
+        let statuses: Vec<&str> = runs.iter().map(|run| run.status.as_str()).collect();
+        assert_eq!(statuses, ["failed", "cancelled", "success"]);
+
+        assert!(
+            runs[0]
+                .error
+                .as_deref()
+                .is_some_and(|error| error.starts_with("Database error"))
+        );
+        assert_eq!(runs[0].shows_processed, 0);
+        assert_eq!(runs[1].shows_processed, 3);
+        assert!(runs[1].error.is_none());
+        assert!(runs[2].full);
+        assert_eq!(runs[2].inserted, 1);
+        assert_eq!(runs[2].warnings, ["/media/tv: library path does not exist"]);
+
+        let (page, total) = list(&db, 1, 1).await.unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(page[0].started_at, 200);
+    }
+}
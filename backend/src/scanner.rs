@@ -1,39 +1,174 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet, hash_map::Entry},
+    ffi::OsStr,
     fs,
+    os::unix::fs::MetadataExt,
     path::{Component, Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
 use chrono::Utc;
-use quick_xml::{Reader, events::Event};
-use serde::Serialize;
-use tracing::{debug, info, warn};
+use serde::{Serialize, Serializer};
+use sqlx::{SqliteConnection, types::Json};
+use tokio::{
+    sync::{RwLock, Semaphore},
+    task::{JoinSet, spawn_blocking},
+};
+use tracing::{debug, error, info, warn};
 use walkdir::WalkDir;
 
-use crate::{config::Config, db::DbPool};
+use crate::{
+    config::{Config, MediaKind},
+    db::DbPool,
+    episodes::{self, EpisodeCandidate},
+    job_retention::{self, PruneError},
+    jobs, mergerfs,
+    nfo::{self, ShowNfo},
+};
+
+#[derive(Clone, Serialize)]
+pub enum ScanState {
+    Idle,
+    Running,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ScanStatus {
+    pub state: ScanState,
+    pub last_started: Option<i64>,
+    pub last_finished: Option<i64>,
+    pub last_error: Option<String>,
+    /// Progress of the running scan.
+    pub progress: Option<ScanProgress>,
+    /// Set by `POST /api/scan/cancel`; the scan stops before its next show or folder walk.
+    pub cancel_requested: CancelFlag,
+}
+
+/// A cancel request shared with the show scans running on the blocking pool,
+/// which check it between their walks of a folder. Serializes as a bool.
+#[derive(Clone, Default)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl CancelFlag {
+    pub fn set(&self, value: bool) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Serialize for CancelFlag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bool(self.is_set())
+    }
+}
+
+impl Default for ScanStatus {
+    fn default() -> Self {
+        Self {
+            state: ScanState::Idle,
+            last_started: None,
+            last_finished: None,
+            last_error: None,
+            progress: None,
+            cancel_requested: CancelFlag::default(),
+        }
+    }
+}
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Default, Serialize)]
+pub struct ScanProgress {
+    /// Library of the show folder scanned last.
+    pub library: Option<String>,
+    pub shows_done: u64,
+    /// Show folders found so far; grows while libraries are still being listed.
+    pub shows_total: u64,
+    pub current_path: Option<String>,
+    pub elapsed_seconds: i64,
+}
+
+impl ScanStatus {
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, ScanState::Running)
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
 pub struct ScanSummary {
     pub scanned_libraries: usize,
     pub shows_processed: usize,
     pub inserted: u64,
     pub updated: u64,
+    /// Shows skipped because their folders are unchanged since the last scan.
+    pub unchanged: u64,
+    /// Shows newly flagged as missing because their folder is gone.
+    pub removed: u64,
+    /// Missing shows deleted, when the scan was asked to purge them.
+    pub purged: u64,
+    /// The scan was cancelled; shows it did not reach were left as they were.
+    pub cancelled: bool,
+    /// Libraries and show folders that could not be read.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ScanOptions {
+    /// Rescan shows whose folder fingerprint is unchanged.
+    pub full: bool,
+    /// Delete missing shows once the scan is done (see `purge_missing`).
+    pub purge_missing: bool,
 }
 
 #[derive(Debug)]
 pub enum ScanError {
     Database(sqlx::Error),
+    /// Purging missing shows failed to archive their jobs.
+    Archive(std::io::Error),
+}
+
+impl std::fmt::Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::Database(err) => write!(f, "Database error: {err}"),
+            ScanError::Archive(err) => write!(f, "Failed to write job archive: {err}"),
+        }
+    }
 }
 
+impl std::error::Error for ScanError {}
+
 impl From<sqlx::Error> for ScanError {
     fn from(value: sqlx::Error) -> Self {
         ScanError::Database(value)
     }
 }
 
+impl From<PruneError> for ScanError {
+    fn from(value: PruneError) -> Self {
+        match value {
+            PruneError::Database(err) => ScanError::Database(err),
+            PruneError::Archive(err) => ScanError::Archive(err),
+        }
+    }
+}
+
+/// What scanning one show folder found.
+enum ShowScan {
+    /// The folder's fingerprint matches the previous scan (carries its path).
+    Unchanged(String),
+    Changed(Box<ShowCandidate>),
+    Skipped,
+}
+
 #[derive(Debug)]
 struct ShowCandidate {
     title: String,
+    kind: MediaKind,
     path: String,
     location: Option<String>,
     size_bytes: i64,
@@ -41,53 +176,235 @@ struct ShowCandidate {
     episode_count: i64,
     thumbnail_path: Option<String>,
     source: String,
+    fingerprint: String,
+    metadata: ShowNfo,
+    episodes: Vec<EpisodeCandidate>,
 }
 
 const VIDEO_EXTENSIONS: &[&str] = &["mkv", "mp4", "avi", "mov", "m4v", "wmv"];
 const THUMBNAIL_NAMES: &[&str] = &["folder.jpg", "poster.jpg", "cover.jpg", "thumb.jpg"];
 
-pub async fn run_scan(config: Config, pool: DbPool) -> Result<ScanSummary, ScanError> {
-    let mut summary = ScanSummary {
-        scanned_libraries: 0,
-        shows_processed: 0,
-        inserted: 0,
-        updated: 0,
-    };
+/// Scans every library. Unless `options.full` is set, shows whose folder
+/// fingerprint matches the one stored by the previous scan are skipped.
+/// Afterwards, shows not seen by the scan whose folder is gone are flagged as
+/// missing.
+///
+/// Show folders are read on the blocking pool, as many at once per tier as its
+/// `scan_parallelism` allows, and libraries on different tiers in parallel.
+/// Results are written `scan.batch_size` shows per transaction.
+///
+/// Progress is reported through `status`. A cancel request stops the scan
+/// before the next show; shows already scanned are still written, but nothing
+/// is flagged as missing.
+pub async fn run_scan(
+    config: Config,
+    pool: DbPool,
+    options: ScanOptions,
+    status: &RwLock<ScanStatus>,
+) -> Result<ScanSummary, ScanError> {
+    let mut summary = ScanSummary::default();
+    let started_at = Utc::now().timestamp();
+    // Libraries that could not be read; their shows are not flagged as missing.
+    let mut unavailable: Vec<&Path> = Vec::new();
+    let config = Arc::new(config);
 
-    let hot_root = sanitize_root(&config.hot_root);
-    let cold_root = sanitize_root(&config.cold_root);
-    let library_paths = resolve_library_paths(&config, hot_root.as_ref(), cold_root.as_ref());
+    let cancel = status.read().await.cancel_requested.clone();
+    let library_paths = resolve_library_paths(&config);
+    // Fastest-tier folders holding only prefetched episodes of a show are not
+    // shows themselves, and neither are copies parked until playback ends.
+    let skipped_folders: HashSet<String> = sqlx::query_scalar(
+        "SELECT hot_path FROM prefetched_files \
+         UNION SELECT destination_path FROM jobs WHERE status = ?",
+    )
+    .bind(jobs::STATUS_WAITING_FOR_PLAYBACK)
+    .fetch_all(&pool)
+    .await?
+    .into_iter()
+    .collect();
+    let previous: HashMap<String, String> = if options.full {
+        HashMap::new()
+    } else {
+        sqlx::query_as::<_, (String, String)>(
+            "SELECT path, scan_fingerprint FROM shows WHERE scan_fingerprint IS NOT NULL",
+        )
+        .fetch_all(&pool)
+        .await?
+        .into_iter()
+        .collect()
+    };
 
-    for library_path in &library_paths {
+    // One limit per tier, shared by every library on it.
+    let mut limits: HashMap<Option<String>, Arc<Semaphore>> = HashMap::new();
+    let mut tasks = JoinSet::new();
+    // Folders by their mergerfs union path, to spot a show split across branches.
+    let mut union_paths: HashMap<PathBuf, String> = HashMap::new();
+    for (index, library_path) in library_paths.iter().enumerate() {
         if !library_path.exists() || !library_path.is_dir() {
             warn!(
                 path = %library_path.display(),
                 "Skipping library path that does not exist or is not a directory"
             );
+            summary.warnings.push(format!(
+                "{}: library path does not exist or is not a directory",
+                library_path.display()
+            ));
+            unavailable.push(library_path);
             continue;
         }
+        if report_progress(status, started_at, |_| {}).await {
+            summary.cancelled = true;
+            break;
+        }
 
         info!(path = %library_path.display(), "Scanning library path");
-        summary.scanned_libraries += 1;
-
-        match scan_library(library_path, hot_root.as_deref(), cold_root.as_deref()) {
-            Ok(mut shows) => {
-                for show in shows.drain(..) {
-                    summary.shows_processed += 1;
-                    match upsert_show(&pool, &show).await? {
-                        UpsertResult::Inserted => summary.inserted += 1,
-                        UpsertResult::Updated => summary.updated += 1,
-                    }
+        let listing = {
+            let library_path = library_path.clone();
+            spawn_blocking(move || list_show_folders(&library_path)).await
+        };
+        let folders = match listing {
+            Ok(Ok((folders, loose_videos))) => {
+                if loose_videos > 0 {
+                    warn!(
+                        path = %library_path.display(),
+                        loose_videos,
+                        "Skipping video files outside a show or movie folder"
+                    );
+                    summary.warnings.push(format!(
+                        "{}: {loose_videos} video file(s) are not inside a folder and were skipped",
+                        library_path.display()
+                    ));
                 }
+                folders
             }
-            Err(err) => {
+            Ok(Err(err)) => {
                 warn!(
                     path = %library_path.display(),
                     error = %err,
                     "Failed to scan library path"
                 );
+                summary
+                    .warnings
+                    .push(format!("{}: {err}", library_path.display()));
+                unavailable.push(library_path);
+                continue;
+            }
+            Err(err) => {
+                error!(?err, path = %library_path.display(), "Library listing task failed");
+                summary
+                    .warnings
+                    .push(format!("{}: {err}", library_path.display()));
+                unavailable.push(library_path);
+                continue;
+            }
+        };
+        summary.scanned_libraries += 1;
+
+        let tier = config.tier_for_path(library_path);
+        let limit = limits
+            .entry(tier.map(|tier| tier.name.clone()))
+            .or_insert_with(|| {
+                let parallelism = tier.map_or(config.scan.parallelism as usize, |tier| {
+                    tier.scan_parallelism()
+                });
+                Arc::new(Semaphore::new(parallelism.max(1)))
+            })
+            .clone();
+        let mut queued = 0;
+        for folder in folders {
+            let key = folder.display().to_string();
+            if skipped_folders.contains(&key) {
+                continue;
             }
+            if let Some(union_path) = mergerfs::union_path_for(&config, &folder) {
+                match union_paths.entry(union_path) {
+                    Entry::Occupied(other) => {
+                        warn!(path = %key, other = %other.get(), "Show folder exists on several mergerfs branches");
+                        summary.warnings.push(format!(
+                            "{key}: also exists at {}; moves of this show are refused until one copy is removed",
+                            other.get()
+                        ));
+                    }
+                    Entry::Vacant(slot) => {
+                        slot.insert(key.clone());
+                    }
+                }
+            }
+            let previous = previous.get(&key).cloned();
+            let config = config.clone();
+            let limit = limit.clone();
+            let cancel = cancel.clone();
+            let library_index = index;
+            tasks.spawn(async move {
+                let _permit = limit.acquire_owned().await;
+                let scan = spawn_blocking(move || {
+                    scan_show(&folder, &config, previous.as_deref(), &cancel)
+                })
+                .await;
+                (library_index, key, scan)
+            });
+            queued += 1;
         }
+        report_progress(status, started_at, |progress| {
+            progress.shows_total += queued;
+        })
+        .await;
+    }
+
+    let batch_size = config.scan.batch_size.max(1) as usize;
+    let mut changed = Vec::new();
+    let mut unchanged = Vec::new();
+    while !summary.cancelled
+        && let Some(result) = tasks.join_next().await
+    {
+        let (library_index, path) = match result {
+            Ok((library_index, path, scan)) => {
+                match scan {
+                    Ok(ShowScan::Unchanged(path)) => unchanged.push(path),
+                    Ok(ShowScan::Changed(candidate)) => {
+                        debug!(path = %candidate.path, "Discovered show candidate");
+                        changed.push(*candidate);
+                    }
+                    Ok(ShowScan::Skipped) => {}
+                    Err(err) => {
+                        error!(?err, %path, "Show scan task failed");
+                        summary.warnings.push(format!("{path}: {err}"));
+                    }
+                }
+                (Some(library_index), Some(path))
+            }
+            Err(err) => {
+                error!(?err, "Show scan task failed");
+                (None, None)
+            }
+        };
+        if changed.len() + unchanged.len() >= batch_size {
+            write_batch(&pool, &mut changed, &mut unchanged, &mut summary).await?;
+        }
+        summary.cancelled = report_progress(status, started_at, |progress| {
+            progress.shows_done += 1;
+            if let Some(library_index) = library_index {
+                progress.library = Some(library_paths[library_index].display().to_string());
+            }
+            progress.current_path = path;
+        })
+        .await;
+    }
+    tasks.abort_all();
+    write_batch(&pool, &mut changed, &mut unchanged, &mut summary).await?;
+
+    if summary.cancelled {
+        info!(
+            processed = summary.shows_processed,
+            unchanged = summary.unchanged,
+            "Filesystem scan cancelled"
+        );
+        return Ok(summary);
+    }
+
+    summary.removed = mark_missing(&pool, started_at, &unavailable).await?;
+    if options.purge_missing {
+        summary.purged =
+            purge_missing(&pool, job_retention::archive_dir(&config).as_deref()).await?;
     }
 
     info!(
@@ -95,18 +412,252 @@ pub async fn run_scan(config: Config, pool: DbPool) -> Result<ScanSummary, ScanE
         processed = summary.shows_processed,
         inserted = summary.inserted,
         updated = summary.updated,
+        unchanged = summary.unchanged,
+        removed = summary.removed,
+        purged = summary.purged,
+        full = options.full,
         "Filesystem scan complete"
     );
 
     Ok(summary)
 }
 
-fn scan_library(
-    library_path: &Path,
-    hot_root: Option<&Path>,
-    cold_root: Option<&Path>,
-) -> Result<Vec<ShowCandidate>, std::io::Error> {
-    let mut shows = Vec::new();
+/// Applies `update` to the running scan's progress and returns whether the
+/// scan was asked to stop.
+async fn report_progress(
+    status: &RwLock<ScanStatus>,
+    started_at: i64,
+    update: impl FnOnce(&mut ScanProgress),
+) -> bool {
+    let mut status = status.write().await;
+    let progress = status.progress.get_or_insert_with(ScanProgress::default);
+    update(progress);
+    progress.elapsed_seconds = Utc::now().timestamp() - started_at;
+    status.cancel_requested.is_set()
+}
+
+/// What `refresh_show` did with a show folder.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ShowRefresh {
+    Inserted,
+    Updated,
+    Removed,
+    /// Nothing to do, or the folder only holds prefetched episodes.
+    Skipped,
+}
+
+/// Rescans a single show folder, or flags its show as missing once the folder is gone.
+pub async fn refresh_show(
+    pool: &DbPool,
+    config: &Config,
+    show_path: &Path,
+) -> Result<ShowRefresh, sqlx::Error> {
+    let path = show_path.display().to_string();
+
+    let is_dir = tokio::fs::metadata(show_path)
+        .await
+        .is_ok_and(|metadata| metadata.is_dir());
+    if !is_dir {
+        let flagged = sqlx::query(
+            "UPDATE shows SET missing_since = ? WHERE path = ? AND missing_since IS NULL",
+        )
+        .bind(Utc::now().timestamp())
+        .bind(&path)
+        .execute(pool)
+        .await?
+        .rows_affected();
+        return Ok(if flagged > 0 {
+            ShowRefresh::Removed
+        } else {
+            ShowRefresh::Skipped
+        });
+    }
+
+    let prefetch_folder: Option<i64> =
+        sqlx::query_scalar("SELECT show_id FROM prefetched_files WHERE hot_path = ? LIMIT 1")
+            .bind(&path)
+            .fetch_optional(pool)
+            .await?;
+    if prefetch_folder.is_some() {
+        return Ok(ShowRefresh::Skipped);
+    }
+
+    // Reading the folder and its NFOs walks the disk, so keep it off the runtime.
+    let candidate = {
+        let show_path = show_path.to_path_buf();
+        let config = config.clone();
+        spawn_blocking(move || {
+            build_show_candidate(&show_path, &config, fingerprint_show(&show_path))
+        })
+        .await
+    };
+    let candidate = match candidate {
+        Ok(Some(candidate)) => candidate,
+        Ok(None) => return Ok(ShowRefresh::Skipped),
+        Err(err) => {
+            error!(?err, %path, "Show refresh task failed");
+            return Ok(ShowRefresh::Skipped);
+        }
+    };
+    let mut conn = pool.acquire().await?;
+    Ok(match upsert_show(&mut conn, &candidate).await? {
+        UpsertResult::Inserted => ShowRefresh::Inserted,
+        UpsertResult::Updated => ShowRefresh::Updated,
+    })
+}
+
+/// Points the show at `from` to its renamed folder `to`, keeping its id, pin
+/// and history. Returns `false` when there is no show at `from` or one
+/// already exists at `to`.
+pub async fn rename_show(
+    pool: &DbPool,
+    config: &Config,
+    from: &Path,
+    to: &Path,
+) -> Result<bool, sqlx::Error> {
+    let location = config.tier_for_path(to).map(|tier| tier.name.clone());
+    let renamed = sqlx::query(
+        r#"
+        UPDATE shows SET path = ?1, location = ?2, missing_since = NULL
+        WHERE path = ?3 AND NOT EXISTS (SELECT 1 FROM shows WHERE path = ?1)
+        "#,
+    )
+    .bind(to.display().to_string())
+    .bind(location)
+    .bind(from.display().to_string())
+    .execute(pool)
+    .await?
+    .rows_affected();
+    Ok(renamed > 0)
+}
+
+/// Writes scanned shows in one transaction and drains both lists. Unchanged
+/// shows only have their `last_scan` bumped.
+async fn write_batch(
+    pool: &DbPool,
+    changed: &mut Vec<ShowCandidate>,
+    unchanged: &mut Vec<String>,
+    summary: &mut ScanSummary,
+) -> Result<(), sqlx::Error> {
+    if changed.is_empty() && unchanged.is_empty() {
+        return Ok(());
+    }
+    let timestamp = Utc::now().timestamp();
+    let mut tx = pool.begin().await?;
+    for path in unchanged.iter() {
+        sqlx::query("UPDATE shows SET last_scan = ?, missing_since = NULL WHERE path = ?")
+            .bind(timestamp)
+            .bind(path)
+            .execute(&mut *tx)
+            .await?;
+    }
+    let mut inserted = 0;
+    let mut updated = 0;
+    for show in changed.iter() {
+        match upsert_show(&mut tx, show).await? {
+            UpsertResult::Inserted => inserted += 1,
+            UpsertResult::Updated => updated += 1,
+        }
+    }
+    tx.commit().await?;
+
+    // Counted only once committed, so a failed batch is not reported as written.
+    summary.unchanged += unchanged.len() as u64;
+    summary.shows_processed += changed.len();
+    summary.inserted += inserted;
+    summary.updated += updated;
+    changed.clear();
+    unchanged.clear();
+    Ok(())
+}
+
+/// Flags shows the scan started at `started_at` did not see and whose folder
+/// no longer exists. Shows inside an `unavailable` library are left alone, so
+/// an unmounted disk does not flag everything on it.
+async fn mark_missing(
+    pool: &DbPool,
+    started_at: i64,
+    unavailable: &[&Path],
+) -> Result<u64, sqlx::Error> {
+    let unseen: Vec<(i64, String)> = sqlx::query_as(
+        r#"
+        SELECT id, path FROM shows
+        WHERE missing_since IS NULL AND (last_scan IS NULL OR last_scan < ?)
+        "#,
+    )
+    .bind(started_at)
+    .fetch_all(pool)
+    .await?;
+
+    let timestamp = Utc::now().timestamp();
+    let mut flagged = 0;
+    for (id, path) in unseen {
+        let show_path = Path::new(&path);
+        if show_path.is_dir()
+            || unavailable
+                .iter()
+                .any(|library| show_path.starts_with(library))
+        {
+            continue;
+        }
+        flagged += sqlx::query("UPDATE shows SET missing_since = ? WHERE id = ?")
+            .bind(timestamp)
+            .bind(id)
+            .execute(pool)
+            .await?
+            .rows_affected();
+        info!(show_id = id, %path, "Show folder is gone; flagged as missing");
+    }
+    Ok(flagged)
+}
+
+/// Deletes missing shows together with their finished jobs, which are written
+/// to the job archive first when `archive_dir` is set. Shows with a queued or
+/// running job are kept until it ends.
+pub async fn purge_missing(pool: &DbPool, archive_dir: Option<&Path>) -> Result<u64, PruneError> {
+    let show_ids: Vec<i64> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM shows s
+        WHERE s.missing_since IS NOT NULL AND NOT EXISTS (
+            SELECT 1 FROM jobs j
+            WHERE j.show_id = s.id AND j.status IN ('queued', 'running', 'waiting_for_playback')
+        )
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    if show_ids.is_empty() {
+        return Ok(0);
+    }
+
+    // Jobs reference their show without ON DELETE, so they have to go first.
+    let archived = job_retention::prune_show_jobs(pool, &show_ids, archive_dir).await?;
+    if archived.deleted > 0 {
+        info!(
+            deleted = archived.deleted,
+            archive = archived.archive_path.as_deref().unwrap_or("-"),
+            "Removed jobs of purged shows"
+        );
+    }
+    let purged = sqlx::query(
+        r#"
+        DELETE FROM shows
+        WHERE missing_since IS NOT NULL
+          AND NOT EXISTS (SELECT 1 FROM jobs WHERE jobs.show_id = shows.id)
+        "#,
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+    Ok(purged)
+}
+
+/// The show and movie folders directly inside `library_path`, and how many
+/// videos lie loose beside them. Only folders can be moved between tiers, so
+/// loose videos are not tracked.
+fn list_show_folders(library_path: &Path) -> Result<(Vec<PathBuf>, usize), std::io::Error> {
+    let mut folders = Vec::new();
+    let mut loose_videos = 0;
     for entry in fs::read_dir(library_path)? {
         let entry = match entry {
             Ok(value) => value,
@@ -120,26 +671,102 @@ fn scan_library(
         };
 
         let path = entry.path();
-        if !path.is_dir() {
-            continue;
+        if path.is_dir() {
+            folders.push(path);
+        } else if is_video_file(&path) {
+            loose_videos += 1;
         }
+    }
+    Ok((folders, loose_videos))
+}
 
-        if let Some(candidate) = build_show_candidate(&path, hot_root, cold_root) {
-            debug!(path = %candidate.path, "Discovered show candidate");
-            shows.push(candidate);
+/// Reads one show folder, skipping the walk when its fingerprint matches `previous`.
+fn scan_show(
+    show_path: &Path,
+    config: &Config,
+    previous: Option<&str>,
+    cancel: &CancelFlag,
+) -> ShowScan {
+    if cancel.is_set() {
+        return ShowScan::Skipped;
+    }
+    let fingerprint = fingerprint_show(show_path);
+    if previous == Some(fingerprint.as_str()) {
+        return ShowScan::Unchanged(show_path.display().to_string());
+    }
+    if cancel.is_set() {
+        return ShowScan::Skipped;
+    }
+    match build_show_candidate(show_path, config, fingerprint) {
+        Some(candidate) => ShowScan::Changed(Box::new(candidate)),
+        None => ShowScan::Skipped,
+    }
+}
+
+/// Hashes the path, mtime and inode of every directory in a show folder.
+/// Adding, removing or renaming a file touches its directory's mtime, so this
+/// changes without reading any file metadata. Files rewritten in place are
+/// missed until a full scan.
+fn fingerprint_show(show_path: &Path) -> String {
+    // FNV-1a, so fingerprints stay comparable across builds.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
         }
+    };
+
+    for entry in WalkDir::new(show_path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_dir())
+    {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let relative = entry.path().strip_prefix(show_path).unwrap_or(entry.path());
+        feed(relative.as_os_str().as_encoded_bytes());
+        feed(&metadata.mtime().to_le_bytes());
+        feed(&metadata.mtime_nsec().to_le_bytes());
+        feed(&metadata.ino().to_le_bytes());
     }
 
-    Ok(shows)
+    format!("{hash:016x}")
 }
 
 fn build_show_candidate(
     show_path: &Path,
-    hot_root: Option<&Path>,
-    cold_root: Option<&Path>,
+    config: &Config,
+    fingerprint: String,
 ) -> Option<ShowCandidate> {
     let folder_title = show_path.file_name()?.to_string_lossy().into_owned();
-    let nfo_title = read_tvshow_title(show_path);
+    // One walk of the folder feeds the stats, the episode index and the NFO count.
+    let files = list_files(show_path);
+    let stats = gather_stats(&files);
+    let episode_files = episodes_from_files(&files);
+    let kind = show_path
+        .parent()
+        .and_then(|library| library_kind(config, library))
+        .unwrap_or_else(|| {
+            detect_kind(
+                show_path,
+                !episode_files.is_empty(),
+                stats.video_episode_count > 0,
+            )
+        });
+
+    let mut metadata = match kind {
+        MediaKind::Series => nfo::read_show_nfo(show_path),
+        MediaKind::Movie => movie_nfo_path(show_path).and_then(|path| nfo::read_movie_nfo(&path)),
+        MediaKind::Other => None,
+    }
+    .unwrap_or_default();
+    if kind == MediaKind::Movie && metadata.year.is_none() {
+        metadata.year = split_title_year(&folder_title).1;
+    }
+    let nfo_title = metadata.title.clone();
     let used_nfo_title = nfo_title.is_some();
     let title = nfo_title.unwrap_or(folder_title);
     let source = if used_nfo_title {
@@ -148,54 +775,172 @@ fn build_show_candidate(
         "fs_scan".to_string()
     };
 
-    let stats = gather_stats(show_path);
-    let nfo_episode_count = count_episode_nfo_files(show_path);
-    let episode_count = if nfo_episode_count > 0 {
-        clamp_to_i64(nfo_episode_count)
+    // Movies and other folders have no seasons or episodes of their own.
+    let (season_count, episode_count, episodes) = if kind == MediaKind::Series {
+        let nfo_episode_count = count_episode_nfo_files(&files);
+        let episode_count = if nfo_episode_count > 0 {
+            clamp_to_i64(nfo_episode_count)
+        } else {
+            stats.video_episode_count
+        };
+        (
+            stats.season_count,
+            episode_count,
+            episodes::index(show_path, episode_files),
+        )
     } else {
-        stats.video_episode_count
+        (0, 0, Vec::new())
     };
 
     Some(ShowCandidate {
         title,
+        kind,
         path: show_path.display().to_string(),
-        location: determine_location(show_path, hot_root, cold_root),
+        location: config
+            .tier_for_path(show_path)
+            .map(|tier| tier.name.clone()),
         size_bytes: stats.total_bytes,
-        season_count: stats.season_count,
+        season_count,
         episode_count,
         thumbnail_path: find_thumbnail(show_path),
         source,
+        fingerprint,
+        metadata,
+        episodes,
     })
 }
 
+/// The kind `library_kinds` sets for folders of the library at
+/// `library_path`, matched directly, through the mergerfs union, or by its
+/// path below its tier root.
+fn library_kind(config: &Config, library_path: &Path) -> Option<MediaKind> {
+    let tier_relative = |path: &Path| {
+        config
+            .locate(path)
+            .and_then(|(_, root)| path.strip_prefix(root).ok().map(Path::to_path_buf))
+    };
+    let library_key = tier_relative(library_path);
+    config
+        .library_kinds
+        .iter()
+        .find(|entry| {
+            let configured = Path::new(entry.library.trim());
+            configured == library_path
+                || mergerfs::branch_paths_for(config, configured)
+                    .is_some_and(|branches| branches.iter().any(|branch| branch == library_path))
+                || (library_key.is_some() && tier_relative(configured) == library_key)
+        })
+        .map(|entry| entry.kind)
+}
+
+/// Classifies a folder of a library without a configured kind. `tvshow.nfo`
+/// or numbered episodes make a series; `movie.nfo`, or a video named exactly
+/// after the folder, with or without its year, a movie. Other folders with videos
+/// stay series, as before kinds existed; folders without any are `Other`.
+fn detect_kind(show_path: &Path, has_numbered_episodes: bool, has_videos: bool) -> MediaKind {
+    if show_path.join("tvshow.nfo").is_file() {
+        return MediaKind::Series;
+    }
+    if show_path.join("movie.nfo").is_file() {
+        return MediaKind::Movie;
+    }
+    if has_numbered_episodes {
+        return MediaKind::Series;
+    }
+
+    let folder_name = show_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let (name, _) = split_title_year(&folder_name);
+    // `Name (2010)/Name.mkv` or `Name (2010)/Name (2010).mkv`, not `Name 2.mkv`.
+    let named_after_folder = top_level_videos(show_path).iter().any(|video| {
+        video.file_stem().is_some_and(|stem| {
+            let stem = stem.to_string_lossy().to_lowercase();
+            stem == name || stem == folder_name.trim()
+        })
+    });
+    if !name.is_empty() && named_after_folder {
+        MediaKind::Movie
+    } else if has_videos {
+        MediaKind::Series
+    } else {
+        MediaKind::Other
+    }
+}
+
+/// A movie folder's `movie.nfo`, or else the NFO named after one of its videos.
+fn movie_nfo_path(movie_path: &Path) -> Option<PathBuf> {
+    let movie_nfo = movie_path.join("movie.nfo");
+    if movie_nfo.is_file() {
+        return Some(movie_nfo);
+    }
+    top_level_videos(movie_path)
+        .into_iter()
+        .map(|video| video.with_extension("nfo"))
+        .find(|path| path.is_file())
+}
+
+fn top_level_videos(folder: &Path) -> Vec<PathBuf> {
+    let mut videos: Vec<PathBuf> = fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && is_video_file(path))
+                .collect()
+        })
+        .unwrap_or_default();
+    videos.sort();
+    videos
+}
+
+/// Splits `Name (2010)` into its name and year.
+fn split_title_year(folder_name: &str) -> (&str, Option<i64>) {
+    if let Some(rest) = folder_name.strip_suffix(')')
+        && let Some((name, year)) = rest.rsplit_once(" (")
+        && year.len() == 4
+        && let Ok(year) = year.parse()
+    {
+        return (name.trim(), Some(year));
+    }
+    (folder_name.trim(), None)
+}
+
 struct ShowStats {
     total_bytes: i64,
     video_episode_count: i64,
     season_count: i64,
 }
 
-fn gather_stats(show_path: &Path) -> ShowStats {
+/// Every file below `show_path`, relative to it, with its size.
+fn list_files(show_path: &Path) -> Vec<(PathBuf, u64)> {
+    WalkDir::new(show_path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(show_path).ok()?.to_path_buf();
+            Some((relative, entry.metadata().map(|m| m.len()).unwrap_or(0)))
+        })
+        .collect()
+}
+
+fn gather_stats(files: &[(PathBuf, u64)]) -> ShowStats {
     let mut total_bytes: u64 = 0;
     let mut episode_count: u64 = 0;
-    let mut season_directories: HashSet<String> = HashSet::new();
-
-    for entry in WalkDir::new(show_path).into_iter().filter_map(Result::ok) {
-        if entry.file_type().is_file() {
-            total_bytes =
-                total_bytes.saturating_add(entry.metadata().map(|m| m.len()).unwrap_or(0));
-
-            if is_video_file(entry.path()) {
-                episode_count = episode_count.saturating_add(1);
-
-                if let Some(parent) = entry.path().parent() {
-                    if let Ok(relative) = parent.strip_prefix(show_path) {
-                        if let Some(component) = relative.components().next() {
-                            if let Component::Normal(name) = component {
-                                season_directories.insert(name.to_string_lossy().into_owned());
-                            }
-                        }
-                    }
-                }
+    let mut season_directories: HashSet<&OsStr> = HashSet::new();
+
+    for (path, size) in files {
+        total_bytes = total_bytes.saturating_add(*size);
+
+        if is_video_file(path) {
+            episode_count = episode_count.saturating_add(1);
+
+            if let Some(parent) = path.parent()
+                && let Some(Component::Normal(name)) = parent.components().next()
+            {
+                season_directories.insert(name);
             }
         }
     }
@@ -213,20 +958,117 @@ fn gather_stats(show_path: &Path) -> ShowStats {
     }
 }
 
-fn determine_location(
-    show_path: &Path,
-    hot_root: Option<&Path>,
-    cold_root: Option<&Path>,
-) -> Option<String> {
-    if let Some(root) = hot_root {
-        if show_path.starts_with(root) {
-            return Some("hot".to_string());
+/// A video file inside a show folder, numbered from its `S01E02`/`1x02` name.
+#[derive(Debug, Clone)]
+pub struct EpisodeFile {
+    pub season: u32,
+    pub episode: u32,
+    /// Video path relative to the show folder.
+    pub relative_path: String,
+    /// Subtitles, NFOs and thumbnails beside the video that share its file stem.
+    pub companion_paths: Vec<String>,
+    /// Size of the video plus its companions.
+    pub size_bytes: i64,
+}
+
+/// Lists the numbered episodes under `show_path` in season/episode order.
+/// Videos whose names carry no episode marker are left out.
+pub fn index_episodes(show_path: &Path) -> Vec<EpisodeFile> {
+    episodes_from_files(&list_files(show_path))
+}
+
+/// `index_episodes` over files already listed by `list_files`.
+fn episodes_from_files(files: &[(PathBuf, u64)]) -> Vec<EpisodeFile> {
+    let mut episodes: Vec<EpisodeFile> = Vec::new();
+    // Numbered videos by their folder and file stem, pointing into `episodes`.
+    let mut by_stem: HashMap<(&Path, &str), usize> = HashMap::new();
+    for (path, size) in files.iter().filter(|(path, _)| is_video_file(path)) {
+        let (Some(name), Some(stem)) = (path.file_name(), path.file_stem()) else {
+            continue;
+        };
+        let Some((season, episode)) = parse_episode_marker(&name.to_string_lossy()) else {
+            continue;
+        };
+        let Some(stem) = stem.to_str() else {
+            continue;
+        };
+        by_stem.insert(
+            (path.parent().unwrap_or(Path::new("")), stem),
+            episodes.len(),
+        );
+        episodes.push(EpisodeFile {
+            season,
+            episode,
+            relative_path: path.to_string_lossy().into_owned(),
+            companion_paths: Vec::new(),
+            size_bytes: clamp_to_i64(*size),
+        });
+    }
+
+    // Companions are named after their video plus suffixes, e.g.
+    // `Show S01E01.en.srt` or `Show S01E01-thumb.jpg`; the longest stem wins.
+    for (path, size) in files.iter().filter(|(path, _)| !is_video_file(path)) {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let parent = path.parent().unwrap_or(Path::new(""));
+        let owner = name
+            .char_indices()
+            .rev()
+            .filter(|(_, c)| matches!(c, '.' | '-'))
+            .find_map(|(index, _)| by_stem.get(&(parent, &name[..index])));
+        if let Some(&index) = owner {
+            let episode = &mut episodes[index];
+            episode.size_bytes = episode.size_bytes.saturating_add(clamp_to_i64(*size));
+            episode
+                .companion_paths
+                .push(path.to_string_lossy().into_owned());
+        }
+    }
+
+    episodes.sort_by(|a, b| {
+        (a.season, a.episode, &a.relative_path).cmp(&(b.season, b.episode, &b.relative_path))
+    });
+    episodes
+}
+
+/// Finds `S01E02` (any case) or `1x02` in a file name. The `x` form takes one
+/// or two season digits and two or three episode digits, so resolutions such
+/// as `1920x1080` are not read as episodes.
+fn parse_episode_marker(name: &str) -> Option<(u32, u32)> {
+    let lower = name.to_ascii_lowercase();
+    let bytes = lower.as_bytes();
+    let boundary = |index: usize| index == 0 || !bytes[index - 1].is_ascii_alphanumeric();
+    let digits_at = |start: usize| {
+        let len = bytes[start..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+        let value = lower.get(start..start + len)?.parse::<u32>().ok()?;
+        Some((value, len))
+    };
+
+    for index in 0..bytes.len() {
+        if bytes[index] == b's'
+            && boundary(index)
+            && let Some((season, len)) = digits_at(index + 1)
+            && bytes.get(index + 1 + len) == Some(&b'e')
+            && let Some((episode, _)) = digits_at(index + 2 + len)
+        {
+            return Some((season, episode));
         }
     }
 
-    if let Some(root) = cold_root {
-        if show_path.starts_with(root) {
-            return Some("cold".to_string());
+    for index in 0..bytes.len() {
+        if bytes[index].is_ascii_digit()
+            && boundary(index)
+            && let Some((season, len)) = digits_at(index)
+            && (1..=2).contains(&len)
+            && bytes.get(index + len) == Some(&b'x')
+            && let Some((episode, episode_len)) = digits_at(index + len + 1)
+            && (2..=3).contains(&episode_len)
+        {
+            return Some((season, episode));
         }
     }
 
@@ -254,60 +1096,10 @@ fn is_video_file(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-fn read_tvshow_title(show_path: &Path) -> Option<String> {
-    let path = show_path.join("tvshow.nfo");
-    let content = fs::read_to_string(path).ok()?;
-    extract_title_from_nfo(&content)
-}
-
-fn extract_title_from_nfo(content: &str) -> Option<String> {
-    let mut reader = Reader::from_str(content);
-    reader.config_mut().trim_text(true);
-    let mut buf = Vec::new();
-    let mut in_title = false;
-
-    loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(element)) => {
-                if element.name().as_ref().eq_ignore_ascii_case(b"title") {
-                    in_title = true;
-                }
-            }
-            Ok(Event::Text(text)) => {
-                if in_title {
-                    if let Ok(value) = text.unescape() {
-                        let trimmed = value.trim();
-                        if !trimmed.is_empty() {
-                            return Some(trimmed.to_string());
-                        }
-                    }
-                }
-            }
-            Ok(Event::End(element)) => {
-                if in_title && element.name().as_ref().eq_ignore_ascii_case(b"title") {
-                    in_title = false;
-                }
-            }
-            Ok(Event::Eof) => break,
-            Err(err) => {
-                warn!("Failed to parse tvshow.nfo: {err}");
-                break;
-            }
-            _ => {}
-        }
-
-        buf.clear();
-    }
-
-    None
-}
-
-fn count_episode_nfo_files(show_path: &Path) -> u64 {
-    WalkDir::new(show_path)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|entry| entry.file_type().is_file())
-        .filter(|entry| is_episode_nfo(entry.path()))
+fn count_episode_nfo_files(files: &[(PathBuf, u64)]) -> u64 {
+    files
+        .iter()
+        .filter(|(path, _)| is_episode_nfo(path))
         .count() as u64
 }
 
@@ -333,29 +1125,54 @@ fn clamp_to_i64(value: u64) -> i64 {
     value.min(i64::MAX as u64) as i64
 }
 
-async fn upsert_show(pool: &DbPool, show: &ShowCandidate) -> Result<UpsertResult, sqlx::Error> {
+async fn upsert_show(
+    conn: &mut SqliteConnection,
+    show: &ShowCandidate,
+) -> Result<UpsertResult, sqlx::Error> {
     let existing_id: Option<i64> = sqlx::query_scalar("SELECT id FROM shows WHERE path = ?")
         .bind(&show.path)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
     let timestamp = Utc::now().timestamp();
 
-    sqlx::query(
+    // Columns owned by users or other subsystems (e.g. `pinned_location`) are
+    // deliberately left out of the update so rescans never reset them.
+    let metadata = &show.metadata;
+    let show_id: i64 = sqlx::query_scalar(
         r#"
         INSERT INTO shows (
             title, path, location, size_bytes, season_count, episode_count,
-            thumbnail_path, source, last_scan
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            thumbnail_path, source, last_scan, first_seen_at, scan_fingerprint,
+            original_title, year, premiered, end_date, rating, series_status,
+            studios, provider_ids, kind
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         ON CONFLICT(path) DO UPDATE SET
             title = excluded.title,
+            kind = excluded.kind,
             location = excluded.location,
             size_bytes = excluded.size_bytes,
             season_count = excluded.season_count,
+            episodes_added_at = CASE
+                WHEN excluded.episode_count > COALESCE(shows.episode_count, 0)
+                    THEN excluded.last_scan
+                ELSE shows.episodes_added_at
+            END,
             episode_count = excluded.episode_count,
             thumbnail_path = excluded.thumbnail_path,
             source = excluded.source,
-            last_scan = excluded.last_scan
+            last_scan = excluded.last_scan,
+            scan_fingerprint = excluded.scan_fingerprint,
+            original_title = excluded.original_title,
+            year = excluded.year,
+            premiered = excluded.premiered,
+            end_date = excluded.end_date,
+            rating = excluded.rating,
+            series_status = excluded.series_status,
+            studios = excluded.studios,
+            provider_ids = excluded.provider_ids,
+            missing_since = NULL
+        RETURNING id
         "#,
     )
     .bind(&show.title)
@@ -367,8 +1184,31 @@ async fn upsert_show(pool: &DbPool, show: &ShowCandidate) -> Result<UpsertResult
     .bind(&show.thumbnail_path)
     .bind(&show.source)
     .bind(timestamp)
-    .execute(pool)
+    .bind(timestamp)
+    .bind(&show.fingerprint)
+    .bind(&metadata.original_title)
+    .bind(metadata.year)
+    .bind(&metadata.premiered)
+    .bind(&metadata.end_date)
+    .bind(metadata.rating)
+    .bind(&metadata.status)
+    .bind(Json(&metadata.studios))
+    .bind(Json(&metadata.provider_ids))
+    .bind(show.kind.as_str())
+    .fetch_one(&mut *conn)
+    .await?;
+
+    link_labels(
+        conn,
+        show_id,
+        &metadata.genres,
+        "genres",
+        "show_genres",
+        "genre_id",
+    )
     .await?;
+    link_labels(conn, show_id, &metadata.tags, "tags", "show_tags", "tag_id").await?;
+    episodes::replace(conn, show_id, &show.episodes).await?;
 
     Ok(if existing_id.is_some() {
         UpsertResult::Updated
@@ -377,6 +1217,41 @@ async fn upsert_show(pool: &DbPool, show: &ShowCandidate) -> Result<UpsertResult
     })
 }
 
+/// Replaces the show's links in `link_table` with `names`, creating missing
+/// rows in `label_table` (`genres` or `tags`). Links keep the NFO's order.
+async fn link_labels(
+    conn: &mut SqliteConnection,
+    show_id: i64,
+    names: &[String],
+    label_table: &str,
+    link_table: &str,
+    label_column: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!("DELETE FROM {link_table} WHERE show_id = ?"))
+        .bind(show_id)
+        .execute(&mut *conn)
+        .await?;
+    for name in names {
+        sqlx::query(&format!(
+            "INSERT INTO {label_table} (name) VALUES (?) ON CONFLICT(name) DO NOTHING"
+        ))
+        .bind(name)
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query(&format!(
+            r#"
+            INSERT OR IGNORE INTO {link_table} (show_id, {label_column})
+            SELECT ?, id FROM {label_table} WHERE name = ?
+            "#
+        ))
+        .bind(show_id)
+        .bind(name)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
 enum UpsertResult {
     Inserted,
     Updated,
@@ -391,11 +1266,9 @@ fn sanitize_root(value: &str) -> Option<PathBuf> {
     }
 }
 
-fn resolve_library_paths(
-    config: &Config,
-    hot_root: Option<&PathBuf>,
-    cold_root: Option<&PathBuf>,
-) -> Vec<PathBuf> {
+/// The folders whose subfolders are shows: `library_paths`, or every tier root
+/// when none are set.
+pub fn resolve_library_paths(config: &Config) -> Vec<PathBuf> {
     let mut libraries = Vec::new();
     let mut seen = HashSet::new();
     let mut has_explicit_paths = false;
@@ -406,15 +1279,23 @@ fn resolve_library_paths(
             continue;
         }
         has_explicit_paths = true;
-        push_library_path(&mut libraries, &mut seen, PathBuf::from(trimmed));
+        // Shows are scanned on the branches behind a mergerfs union, so their
+        // paths say which tier holds them.
+        match mergerfs::branch_paths_for(config, Path::new(trimmed)) {
+            Some(branch_paths) => {
+                for path in branch_paths.into_iter().filter(|path| path.is_dir()) {
+                    push_library_path(&mut libraries, &mut seen, path);
+                }
+            }
+            None => push_library_path(&mut libraries, &mut seen, PathBuf::from(trimmed)),
+        }
     }
 
     if !has_explicit_paths {
-        if let Some(path) = hot_root {
-            push_library_path(&mut libraries, &mut seen, path.clone());
-        }
-        if let Some(path) = cold_root {
-            push_library_path(&mut libraries, &mut seen, path.clone());
+        for root in config.tiers.iter().flat_map(|tier| &tier.roots) {
+            if let Some(path) = sanitize_root(root) {
+                push_library_path(&mut libraries, &mut seen, path);
+            }
         }
     }
 
@@ -435,17 +1316,229 @@ fn push_library_path(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{TempDir, test_pool};
 
     #[test]
     fn reads_title_from_sample_tvshow_nfo() {
         let show_dir = Path::new("../Example");
-        let title = read_tvshow_title(show_dir);
-        assert_eq!(title.as_deref(), Some("Summer Time Rendering"));
+        let candidate = build_show_candidate(show_dir, &Config::default(), String::new()).unwrap();
+        assert_eq!(candidate.title, "Summer Time Rendering");
+        assert_eq!(candidate.source, "fs_scan_nfo");
     }
 
     #[test]
     fn counts_episode_nfo_files_in_sample() {
         let show_dir = Path::new("../Example");
-        assert_eq!(count_episode_nfo_files(show_dir), 1);
+        assert_eq!(count_episode_nfo_files(&list_files(show_dir)), 1);
+    }
+
+    #[test]
+    fn parses_episode_markers_from_file_names() {
+        assert_eq!(
+            parse_episode_marker("Show - S02E05 - Title.mkv"),
+            Some((2, 5))
+        );
+        assert_eq!(parse_episode_marker("show.s1e12.720p.mp4"), Some((1, 12)));
+        assert_eq!(parse_episode_marker("Show 3x07.avi"), Some((3, 7)));
+        assert_eq!(parse_episode_marker("Episode 7.mkv"), None);
+        assert_eq!(parse_episode_marker("Show 1920x1080.mkv"), None);
+    }
+
+    #[test]
+    fn groups_companions_by_folder_and_stem() {
+        let files: Vec<(PathBuf, u64)> = [
+            ("Season 1/Show S01E01.mkv", 100),
+            ("Season 1/Show S01E01.en.srt", 2),
+            ("Season 1/Show S01E01-thumb.jpg", 3),
+            ("Season 1/Show S01E01.nfo", 1),
+            ("Season 1/Show S01E010.srt", 5),
+            ("Season 1/Show S01E02.mkv", 200),
+            ("Season 2/Show S01E01.srt", 7),
+            ("tvshow.nfo", 1),
+        ]
+        .into_iter()
+        .map(|(path, size)| (PathBuf::from(path), size))
+        .collect();
+
+        let mut episodes = episodes_from_files(&files);
+        episodes.sort_by_key(|episode| episode.episode);
+        assert_eq!(episodes.len(), 2);
+        assert_eq!(episodes[0].size_bytes, 106);
+        let mut companions = episodes[0].companion_paths.clone();
+        companions.sort();
+        assert_eq!(
+            companions,
+            [
+                "Season 1/Show S01E01-thumb.jpg",
+                "Season 1/Show S01E01.en.srt",
+                "Season 1/Show S01E01.nfo"
+            ]
+        );
+        assert!(episodes[1].companion_paths.is_empty());
+
+        let stats = gather_stats(&files);
+        assert_eq!(stats.season_count, 1);
+        assert_eq!(stats.video_episode_count, 2);
+        assert_eq!(stats.total_bytes, 319);
+        assert_eq!(count_episode_nfo_files(&files), 1);
+    }
+
+    #[test]
+    fn fingerprint_changes_when_a_season_folder_changes() {
+        let show = TempDir::new("fingerprint");
+        let show_dir = show.path();
+        fs::create_dir_all(show_dir.join("Season 01")).unwrap();
+
+        let before = fingerprint_show(show_dir);
+        assert_eq!(fingerprint_show(show_dir), before);
+        // A new folder is picked up through its path and inode, whatever the
+        // resolution of the mtime clock.
+        fs::create_dir(show_dir.join("Season 01/Extras")).unwrap();
+        assert_ne!(fingerprint_show(show_dir), before);
+    }
+
+    #[test]
+    fn classifies_movie_and_series_folders() {
+        let dir = TempDir::new("kinds");
+        let library = dir.path();
+        let movie = library.join("Inception (2010)");
+        let series = library.join("Some Show");
+        let other = library.join("Artwork");
+        fs::create_dir_all(&movie).unwrap();
+        fs::create_dir_all(series.join("Season 1")).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        fs::write(movie.join("Inception.mkv"), b"").unwrap();
+        fs::write(
+            movie.join("Inception.nfo"),
+            "<movie><title>Inception</title><genre>Sci-Fi</genre></movie>",
+        )
+        .unwrap();
+        fs::write(series.join("Season 1/Some Show S01E01.mkv"), b"").unwrap();
+        fs::write(other.join("poster.jpg"), b"").unwrap();
+
+        let config = Config::default();
+        let candidate = build_show_candidate(&movie, &config, String::new()).unwrap();
+        assert_eq!(candidate.kind, MediaKind::Movie);
+        assert_eq!(candidate.title, "Inception");
+        assert_eq!(candidate.metadata.year, Some(2010));
+        assert_eq!(candidate.metadata.genres, vec!["Sci-Fi"]);
+        assert_eq!(candidate.episode_count, 0);
+        let candidate = build_show_candidate(&series, &config, String::new()).unwrap();
+        assert_eq!(candidate.kind, MediaKind::Series);
+        assert_eq!(candidate.episodes.len(), 1);
+        let candidate = build_show_candidate(&other, &config, String::new()).unwrap();
+        assert_eq!(candidate.kind, MediaKind::Other);
+
+        // The video has to be named after the folder, not merely start with its name.
+        let with_year = library.join("Heat (1995)");
+        let sequel = library.join("Alien");
+        fs::create_dir_all(&with_year).unwrap();
+        fs::create_dir_all(&sequel).unwrap();
+        fs::write(with_year.join("Heat (1995).mkv"), b"").unwrap();
+        fs::write(sequel.join("Alien 2.mkv"), b"").unwrap();
+        assert_eq!(detect_kind(&with_year, false, true), MediaKind::Movie);
+        assert_eq!(detect_kind(&sequel, false, true), MediaKind::Series);
+
+        let config = Config {
+            library_paths: vec![library.display().to_string()],
+            library_kinds: vec![crate::config::LibraryKind {
+                library: library.display().to_string(),
+                kind: MediaKind::Movie,
+            }],
+            ..Config::default()
+        };
+        let candidate = build_show_candidate(&series, &config, String::new()).unwrap();
+        assert_eq!(candidate.kind, MediaKind::Movie);
+    }
+
+    #[tokio::test]
+    async fn purging_missing_shows_archives_their_jobs() {
+        let (_db_dir, db) = test_pool().await;
+        let archive_dir = TempDir::new("purge-archive");
+        sqlx::query(
+            "INSERT INTO shows (id, title, path, missing_since) VALUES \
+             (1, 'Gone', '/media/cold/tv/Gone', 10), (2, 'Busy', '/media/cold/tv/Busy', 10), \
+             (3, 'Here', '/media/cold/tv/Here', NULL)",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        for (id, show_id, status) in [(1, 1, "success"), (2, 1, "failed"), (3, 2, "queued")] {
+            sqlx::query(
+                "INSERT INTO jobs (id, show_id, source_path, destination_path, status, \
+                 created_at, updated_at, finished_at) VALUES (?, ?, '/a', '/b', ?, 0, 0, 0)",
+            )
+            .bind(id)
+            .bind(show_id)
+            .bind(status)
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            purge_missing(&db, Some(archive_dir.path())).await.unwrap(),
+            1
+        );
+
+        let shows: Vec<i64> = sqlx::query_scalar("SELECT id FROM shows ORDER BY id")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(shows, vec![2, 3]);
+        let jobs: Vec<i64> = sqlx::query_scalar("SELECT id FROM jobs")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(jobs, vec![3]);
+        assert_eq!(fs::read_dir(archive_dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn scans_skip_copies_parked_for_playback() {
+        let (_db_dir, db) = test_pool().await;
+        let hot = TempDir::new("parked-hot");
+        let cold = TempDir::new("parked-cold");
+        for root in [&hot, &cold] {
+            fs::create_dir_all(root.path().join("Show")).unwrap();
+            fs::write(root.path().join("Show/Show S01E01.mkv"), b"video").unwrap();
+        }
+        let source = cold.path().join("Show").display().to_string();
+        let destination = hot.path().join("Show").display().to_string();
+        sqlx::query("INSERT INTO shows (id, title, path, location) VALUES (1, 'Show', ?, 'cold')")
+            .bind(&source)
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO jobs (show_id, source_path, destination_path, status, created_at, \
+             updated_at) VALUES (1, ?, ?, ?, 0, 0)",
+        )
+        .bind(&source)
+        .bind(&destination)
+        .bind(jobs::STATUS_WAITING_FOR_PLAYBACK)
+        .execute(&db)
+        .await
+        .unwrap();
+        let tier = |name: &str, root: &TempDir| crate::config::TierConfig {
+            name: name.to_string(),
+            roots: vec![root.path().display().to_string()],
+            ..Default::default()
+        };
+        let config = Config {
+            tiers: vec![tier("hot", &hot), tier("cold", &cold)],
+            ..Config::default()
+        };
+
+        let status = RwLock::new(ScanStatus::default());
+        run_scan(config, db.clone(), ScanOptions::default(), &status)
+            .await
+            .unwrap();
+
+        let shows: Vec<String> = sqlx::query_scalar("SELECT path FROM shows")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(shows, vec![source]);
     }
 }
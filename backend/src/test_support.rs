@@ -0,0 +1,48 @@
+//! Fixtures shared by unit tests.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::db::{self, DbPool};
+
+/// A scratch directory under the system temp dir, removed on drop.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates an empty directory whose name includes `label`, the process id
+    /// and a counter, so parallel tests never share one.
+    pub fn new(label: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "jellymover-{label}-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("failed to create temp dir");
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A database with the full schema, stored in its own temp dir. Keep the
+/// returned dir alive for as long as the pool is used.
+pub async fn test_pool() -> (TempDir, DbPool) {
+    let dir = TempDir::new("db");
+    let pool = db::init_pool(dir.path().join("test.db"))
+        .await
+        .expect("failed to open test database");
+    (dir, pool)
+}
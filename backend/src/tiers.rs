@@ -0,0 +1,284 @@
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::{
+    config::{Config, PlacementStrategy, TierConfig},
+    db::DbPool,
+    jobs, pools,
+};
+
+/// Re-derives tier names stored on rows from their paths, so shows and jobs
+/// stay labelled correctly after tiers are added, renamed or re-rooted.
+/// Jobs only get a tier filled in where it is missing; the tiers a finished
+/// job ran between are history.
+pub async fn relabel_rows(db: &DbPool, config: &Config) -> Result<(), sqlx::Error> {
+    let mut shows = 0;
+    let mut jobs_labelled = 0;
+
+    for tier in &config.tiers {
+        for root in tier.root_paths() {
+            let prefix = jobs::root_prefix(&root.to_string_lossy());
+
+            shows += sqlx::query(
+                r#"
+                UPDATE shows SET location = ?1
+                WHERE substr(path, 1, length(?2)) = ?2 AND location IS NOT ?1
+                "#,
+            )
+            .bind(&tier.name)
+            .bind(&prefix)
+            .execute(db)
+            .await?
+            .rows_affected();
+
+            jobs_labelled += sqlx::query(
+                r#"
+                UPDATE jobs SET source_tier = ?1
+                WHERE source_tier IS NULL AND substr(source_path, 1, length(?2)) = ?2
+                "#,
+            )
+            .bind(&tier.name)
+            .bind(&prefix)
+            .execute(db)
+            .await?
+            .rows_affected();
+
+            jobs_labelled += sqlx::query(
+                r#"
+                UPDATE jobs SET target_tier = ?1
+                WHERE target_tier IS NULL AND substr(destination_path, 1, length(?2)) = ?2
+                "#,
+            )
+            .bind(&tier.name)
+            .bind(&prefix)
+            .execute(db)
+            .await?
+            .rows_affected();
+        }
+    }
+
+    if shows > 0 || jobs_labelled > 0 {
+        info!(
+            shows,
+            jobs = jobs_labelled,
+            "Relabelled rows for configured tiers"
+        );
+    }
+    Ok(())
+}
+
+/// What `choose_root` knows about one root of the target tier.
+#[derive(Debug, Clone)]
+struct RootState {
+    root: PathBuf,
+    /// The show's folder already exists under this root.
+    holds_show: bool,
+    /// The folder of the show's library already exists under this root.
+    holds_library: bool,
+    /// `None` when the root's usage cannot be read.
+    free_bytes: Option<u64>,
+    /// Bytes of queued and running moves into this root.
+    incoming_bytes: i64,
+}
+
+/// Picks the root of `tier` that a show at `relative` (its path below its
+/// current root) should move into. A root that already holds the show's
+/// folder, e.g. from prefetched episodes, always wins; otherwise the tier's
+/// placement strategy decides. Returns `None` when the tier has no roots.
+pub async fn choose_root(
+    db: &DbPool,
+    tier: &TierConfig,
+    relative: &Path,
+) -> Result<Option<PathBuf>, sqlx::Error> {
+    let roots = tier.root_paths();
+    if roots.len() <= 1 {
+        return Ok(roots.into_iter().next());
+    }
+
+    let relative = relative.to_path_buf();
+    let mut states = tokio::task::spawn_blocking(move || {
+        // The library is the folder the show sits in, relative to its root.
+        let library = relative
+            .parent()
+            .filter(|library| !library.as_os_str().is_empty());
+        roots
+            .into_iter()
+            .map(|root| RootState {
+                holds_show: root.join(&relative).is_dir(),
+                holds_library: library.is_some_and(|library| root.join(library).is_dir()),
+                free_bytes: pools::collect_pool_usage(&root.to_string_lossy())
+                    .map(|pool| pool.free_bytes),
+                incoming_bytes: 0,
+                root,
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+    if states.is_empty() {
+        return Ok(tier.root_paths().into_iter().next());
+    }
+
+    for state in states.iter_mut().filter(|state| state.free_bytes.is_some()) {
+        let prefix = jobs::root_prefix(&state.root.to_string_lossy());
+        (_, state.incoming_bytes) = jobs::pending_transfer_bytes(db, &prefix).await?;
+    }
+
+    let previous: Option<String> = if tier.placement == PlacementStrategy::RoundRobin {
+        sqlx::query_scalar(
+            r#"
+            SELECT destination_path FROM jobs
+            WHERE kind = ? AND target_tier = ?
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(jobs::KIND_MOVE)
+        .bind(&tier.name)
+        .fetch_optional(db)
+        .await?
+    } else {
+        None
+    };
+
+    Ok(pick_root(
+        tier.placement,
+        &states,
+        previous.as_deref().map(Path::new),
+    ))
+}
+
+/// `choose_root` without the I/O. `previous` is the destination of the last
+/// move into the tier, for round robin.
+fn pick_root(
+    placement: PlacementStrategy,
+    roots: &[RootState],
+    previous: Option<&Path>,
+) -> Option<PathBuf> {
+    if let Some(state) = roots.iter().find(|state| state.holds_show) {
+        return Some(state.root.clone());
+    }
+
+    match placement {
+        PlacementStrategy::MostFree => most_free(roots),
+        PlacementStrategy::RoundRobin => {
+            let next = previous
+                .and_then(|path| roots.iter().position(|state| path.starts_with(&state.root)))
+                .map_or(0, |index| (index + 1) % roots.len());
+            roots.get(next).map(|state| state.root.clone())
+        }
+        PlacementStrategy::StickyPerLibrary => {
+            let holding: Vec<RootState> = roots
+                .iter()
+                .filter(|state| state.holds_library)
+                .cloned()
+                .collect();
+            if holding.is_empty() {
+                most_free(roots)
+            } else {
+                most_free(&holding)
+            }
+        }
+    }
+}
+
+/// The root with the most free space after queued and running moves into it
+/// finish. Unreadable roots are skipped; if none can be read the first wins.
+fn most_free(roots: &[RootState]) -> Option<PathBuf> {
+    let mut best: Option<(i64, &PathBuf)> = None;
+
+    for state in roots {
+        let Some(free_bytes) = state.free_bytes else {
+            continue;
+        };
+        let free = (free_bytes.min(i64::MAX as u64) as i64).saturating_sub(state.incoming_bytes);
+        if best.is_none_or(|(most, _)| free > most) {
+            best = Some((free, &state.root));
+        }
+    }
+
+    best.map(|(_, root)| root.clone())
+        .or_else(|| roots.first().map(|state| state.root.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(path: &str, free_bytes: Option<u64>, incoming_bytes: i64) -> RootState {
+        RootState {
+            root: PathBuf::from(path),
+            holds_show: false,
+            holds_library: false,
+            free_bytes,
+            incoming_bytes,
+        }
+    }
+
+    #[test]
+    fn most_free_counts_incoming_moves_and_skips_unreadable_roots() {
+        let roots = [
+            root("/media/a", Some(500), 0),
+            root("/media/b", Some(800), 400),
+            root("/media/c", None, 0),
+        ];
+        assert_eq!(most_free(&roots), Some(PathBuf::from("/media/a")));
+
+        let unreadable = [root("/media/b", None, 0), root("/media/c", None, 0)];
+        assert_eq!(most_free(&unreadable), Some(PathBuf::from("/media/b")));
+        assert_eq!(most_free(&[]), None);
+    }
+
+    #[test]
+    fn sticky_placement_prefers_roots_holding_the_library() {
+        let mut roots = [
+            root("/media/a", Some(900), 0),
+            root("/media/b", Some(100), 0),
+            root("/media/c", Some(300), 0),
+        ];
+        let sticky = PlacementStrategy::StickyPerLibrary;
+        assert_eq!(
+            pick_root(sticky, &roots, None),
+            Some(PathBuf::from("/media/a"))
+        );
+
+        roots[1].holds_library = true;
+        roots[2].holds_library = true;
+        assert_eq!(
+            pick_root(sticky, &roots, None),
+            Some(PathBuf::from("/media/c"))
+        );
+
+        // The show's own folder beats every strategy.
+        roots[1].holds_show = true;
+        assert_eq!(
+            pick_root(sticky, &roots, None),
+            Some(PathBuf::from("/media/b"))
+        );
+    }
+
+    #[test]
+    fn round_robin_follows_the_previous_move() {
+        let roots = [root("/media/a", None, 0), root("/media/b", None, 0)];
+        let next = |previous: Option<&str>| {
+            pick_root(
+                PlacementStrategy::RoundRobin,
+                &roots,
+                previous.map(Path::new),
+            )
+        };
+        assert_eq!(next(None), Some(PathBuf::from("/media/a")));
+        assert_eq!(
+            next(Some("/media/a/tv/One")),
+            Some(PathBuf::from("/media/b"))
+        );
+        assert_eq!(
+            next(Some("/media/b/tv/One")),
+            Some(PathBuf::from("/media/a"))
+        );
+        assert_eq!(
+            next(Some("/elsewhere/tv/One")),
+            Some(PathBuf::from("/media/a"))
+        );
+    }
+}
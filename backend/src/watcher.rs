@@ -0,0 +1,230 @@
+use notify::{
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    event::{ModifyKind, RenameMode},
+};
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    sync::{RwLock, mpsc},
+    task::JoinHandle,
+    time::{Duration, Instant, interval},
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{self, Config},
+    db::DbPool,
+    farm, jobs,
+    scanner::{self, ScanStatus, ShowRefresh},
+};
+
+/// Start the background task that rescans show folders as they change on disk.
+/// The watched folders follow the config, so enabling the watcher or changing
+/// libraries takes effect without a restart.
+pub fn start_watcher(
+    db: DbPool,
+    config: Arc<RwLock<Config>>,
+    scan_status: Arc<RwLock<ScanStatus>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Starting library watcher background task");
+
+        let (tx, mut rx) = mpsc::unbounded_channel::<notify::Result<Event>>();
+        let mut watcher: Option<RecommendedWatcher> = None;
+        let mut libraries: Vec<PathBuf> = Vec::new();
+        // Show folders with unprocessed changes, and when they last changed.
+        let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+        let mut tick = interval(Duration::from_secs(1));
+
+        loop {
+            tokio::select! {
+                Some(result) = rx.recv() => {
+                    let event = match result {
+                        Ok(event) => event,
+                        Err(err) => {
+                            warn!(%err, "Library watcher reported an error");
+                            continue;
+                        }
+                    };
+                    if event.need_rescan() {
+                        warn!("Library watcher dropped events; run a scan to catch up");
+                    }
+                    handle_event(&db, &config, &libraries, &mut pending, event).await;
+                }
+                _ = tick.tick() => {
+                    let config_snapshot = config.read().await.clone();
+                    let wanted = watched_libraries(&config_snapshot);
+                    if wanted != libraries {
+                        watcher = None;
+                        if !wanted.is_empty() {
+                            watcher = watch(&wanted, tx.clone());
+                        }
+                        libraries = wanted;
+                        pending.clear();
+                    }
+                    if watcher.is_none() || scan_status.read().await.is_running() {
+                        continue;
+                    }
+
+                    let debounce_seconds = config_snapshot.watcher.debounce_seconds.max(1);
+                    let debounce = Duration::from_secs(u64::from(debounce_seconds));
+                    let due: Vec<PathBuf> = pending
+                        .iter()
+                        .filter(|(_, changed)| changed.elapsed() >= debounce)
+                        .map(|(show_path, _)| show_path.clone())
+                        .collect();
+                    for show_path in due {
+                        pending.remove(&show_path);
+                        match refresh(&db, &config_snapshot, &show_path).await {
+                            Ok(true) => {}
+                            // A job is still copying; look again after another quiet period.
+                            Ok(false) => {
+                                pending.insert(show_path, Instant::now());
+                            }
+                            Err(err) => error!(
+                                ?err,
+                                path = %show_path.display(),
+                                "Failed to rescan changed show"
+                            ),
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Library folders to watch; empty while the watcher is disabled or setup is incomplete.
+fn watched_libraries(config: &Config) -> Vec<PathBuf> {
+    if !config.watcher.enabled || !config::config_is_ready(config) {
+        return Vec::new();
+    }
+    scanner::resolve_library_paths(config)
+        .into_iter()
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+fn watch(
+    libraries: &[PathBuf],
+    tx: mpsc::UnboundedSender<notify::Result<Event>>,
+) -> Option<RecommendedWatcher> {
+    let mut watcher = match notify::recommended_watcher(move |result| {
+        let _ = tx.send(result);
+    }) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            error!(%err, "Failed to start library watcher");
+            return None;
+        }
+    };
+
+    for library in libraries {
+        match watcher.watch(library, RecursiveMode::Recursive) {
+            Ok(()) => info!(path = %library.display(), "Watching library for changes"),
+            Err(err) => warn!(%err, path = %library.display(), "Failed to watch library"),
+        }
+    }
+    Some(watcher)
+}
+
+async fn handle_event(
+    db: &DbPool,
+    config: &RwLock<Config>,
+    libraries: &[PathBuf],
+    pending: &mut HashMap<PathBuf, Instant>,
+    event: Event,
+) {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+
+    // A show folder renamed within its library keeps its row.
+    if let EventKind::Modify(ModifyKind::Name(RenameMode::Both)) = event.kind
+        && let [from, to] = event.paths.as_slice()
+        && show_folder(libraries, from).as_ref() == Some(from)
+        && show_folder(libraries, to).as_ref() == Some(to)
+    {
+        let config = config.read().await;
+        match scanner::rename_show(db, &config, from, to).await {
+            Ok(true) => info!(
+                from = %from.display(),
+                to = %to.display(),
+                "Show folder renamed"
+            ),
+            Ok(false) => {}
+            Err(err) => error!(?err, from = %from.display(), "Failed to rename show"),
+        }
+    }
+
+    let now = Instant::now();
+    for path in &event.paths {
+        if let Some(show_path) = show_folder(libraries, path) {
+            pending.insert(show_path, now);
+        }
+    }
+}
+
+/// The show folder `path` belongs to: the first folder below its library.
+fn show_folder(libraries: &[PathBuf], path: &Path) -> Option<PathBuf> {
+    libraries.iter().find_map(|library| {
+        let relative = path.strip_prefix(library).ok()?;
+        match relative.components().next()? {
+            Component::Normal(name) => Some(library.join(name)),
+            _ => None,
+        }
+    })
+}
+
+/// Rescans one changed show folder. Returns `false` without touching it
+/// while a job is moving files into or out of it.
+async fn refresh(db: &DbPool, config: &Config, show_path: &Path) -> Result<bool, sqlx::Error> {
+    let path = show_path.display().to_string();
+    if jobs::has_active_job_at(db, &path).await? {
+        return Ok(false);
+    }
+
+    let outcome = scanner::refresh_show(db, config, show_path).await?;
+    match outcome {
+        ShowRefresh::Inserted | ShowRefresh::Updated => {
+            info!(%path, ?outcome, "Rescanned changed show");
+            if let Some(link) = farm::link_for(config, show_path)
+                && let Err(err) = farm::point_link(&link, show_path)
+            {
+                warn!(?err, link = %link.display(), "Failed to update stable path");
+            }
+        }
+        ShowRefresh::Removed => info!(%path, "Show folder removed"),
+        ShowRefresh::Skipped => {}
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_changed_paths_to_show_folders() {
+        let libraries = vec![PathBuf::from("/media/hot/tv"), PathBuf::from("/media/cold")];
+        assert_eq!(
+            show_folder(
+                &libraries,
+                Path::new("/media/hot/tv/Andor/Season 1/e01.mkv")
+            ),
+            Some(PathBuf::from("/media/hot/tv/Andor"))
+        );
+        assert_eq!(
+            show_folder(&libraries, Path::new("/media/cold/Heat")),
+            Some(PathBuf::from("/media/cold/Heat"))
+        );
+        assert_eq!(show_folder(&libraries, Path::new("/media/hot/tv")), None);
+        assert_eq!(
+            show_folder(&libraries, Path::new("/media/hot/anime/x")),
+            None
+        );
+    }
+}
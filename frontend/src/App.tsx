@@ -11,9 +11,8 @@ import { useTheme } from './theme'
 
 const needsConfigSetup = (config: AppConfig | null) => {
   if (!config) return true
-  const hotMissing = !config.hot_root.trim()
-  const coldMissing = !config.cold_root.trim()
-  return hotMissing || coldMissing
+  const tiers = config.tiers ?? []
+  return tiers.length < 2 || tiers.some((tier) => !tier.roots.some((root) => root.trim()))
 }
 
 function AppLayout() {
@@ -5,6 +5,7 @@ import { deriveShowTag } from './JellyMoverShell.helpers'
 const baseShow: Show = {
   id: 1,
   title: 'Test',
+  kind: 'series',
   path: '/media/test',
   location: null,
   size_bytes: null,
@@ -24,6 +25,11 @@ describe('deriveShowTag', () => {
     expect(deriveShowTag(show)).toBe('EPISODES')
   })
 
+  it('returns MOVIE for movies', () => {
+    const show: Show = { ...baseShow, kind: 'movie', location: 'hot' }
+    expect(deriveShowTag(show)).toBe('MOVIE')
+  })
+
   it('uses the location when available', () => {
     const show = { ...baseShow, location: '  hot ' }
     expect(deriveShowTag(show)).toBe('HOT')
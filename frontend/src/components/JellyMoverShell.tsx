@@ -2,7 +2,7 @@ import { useCallback, useEffect, useMemo, useRef, useState } from 'react'
 import type { CSSProperties } from 'react'
 import { useNavigate } from 'react-router-dom'
 import { apiGet, apiPost } from '../api'
-import type { PoolsResponse, Show, SortDirection, SortField, SystemStats, ScanStatus, JobAnalytics } from '../types'
+import type { Page, PoolsResponse, Show, SortDirection, SortField, SystemStats, ScanStatus, JobAnalytics } from '../types'
 import type { Job } from '../types'
 import AppHeader from './AppHeader'
 import {
@@ -99,6 +99,11 @@ const JellyMoverShell = () => {
     }
   }, [])
 
+  // The two columns show the fastest and slowest configured tiers
+  const hotTier = pools?.tiers[0] ?? null
+  const coldTier = pools && pools.tiers.length > 1 ? pools.tiers[pools.tiers.length - 1] : null
+  const hotTierName = hotTier?.name ?? 'hot'
+  const coldTierName = coldTier?.name ?? 'cold'
 
   const fetchShowsPage = useCallback(
     async (
@@ -129,7 +134,7 @@ const JellyMoverShell = () => {
       }
 
       const params = new URLSearchParams({
-        location,
+        location: location === 'hot' ? hotTierName : coldTierName,
         limit: SHOWS_PAGE_SIZE.toString(),
         offset: requestOffset.toString(),
       })
@@ -148,12 +153,13 @@ const JellyMoverShell = () => {
       }
 
       try {
-        const data = await apiGet<Show[]>(`/shows?${params.toString()}`)
+        const page = await apiGet<Page<Show>>(`/shows?${params.toString()}`)
         if (!isMountedRef.current) return
+        const data = page.items
         setMedia((prev) => (reset ? data : [...prev, ...data]))
         setPagination({
           offset: requestOffset + data.length,
-          hasMore: data.length === SHOWS_PAGE_SIZE,
+          hasMore: requestOffset + data.length < page.total,
           loading: false,
         })
       } catch (err) {
@@ -167,7 +173,7 @@ const JellyMoverShell = () => {
         }))
       }
     },
-    [],
+    [hotTierName, coldTierName],
   )
 
   const loadHotShows = useCallback(
@@ -299,11 +305,13 @@ const JellyMoverShell = () => {
     }
   }, [])
 
-  const hotUsagePercent = pools?.hot && pools.hot.total_bytes > 0
-    ? Math.round((pools.hot.used_bytes / pools.hot.total_bytes) * 100)
+  const hotPool = hotTier?.usage ?? null
+  const coldPool = coldTier?.usage ?? null
+  const hotUsagePercent = hotPool && hotPool.total_bytes > 0
+    ? Math.round((hotPool.used_bytes / hotPool.total_bytes) * 100)
     : null
-  const coldUsagePercent = pools?.cold && pools.cold.total_bytes > 0
-    ? Math.round((pools.cold.used_bytes / pools.cold.total_bytes) * 100)
+  const coldUsagePercent = coldPool && coldPool.total_bytes > 0
+    ? Math.round((coldPool.used_bytes / coldPool.total_bytes) * 100)
     : null
 
   useEffect(() => {
@@ -331,7 +339,7 @@ const JellyMoverShell = () => {
     updateMovingState(show.id, true)
     setMoveError(null)
     try {
-      await apiPost(`/shows/${show.id}/move`, { target })
+      await apiPost(`/shows/${show.id}/move`, { target: target === 'hot' ? hotTierName : coldTierName })
     } catch (err) {
       console.error('Failed to enqueue move', err)
       const message = err instanceof Error ? err.message : 'Unknown error'
@@ -443,14 +451,16 @@ const JellyMoverShell = () => {
   const busyShowIds = useMemo(() => {
     const set = new Set<number>()
     jobs.forEach((job) => {
-      if (job.status === 'queued' || job.status === 'running') {
+      if (job.status === 'queued' || job.status === 'running' || job.status === 'waiting_for_playback') {
         set.add(job.show_id)
       }
     })
     return set
   }, [jobs])
 
-  const queueActive = analytics ? analytics.running_count + analytics.queued_count : jobs.filter((job) => job.status === 'queued' || job.status === 'running').length
+  const queueActive = analytics
+    ? analytics.running_count + analytics.queued_count + (analytics.waiting_for_playback_count ?? 0)
+    : jobs.filter((job) => job.status === 'queued' || job.status === 'running' || job.status === 'waiting_for_playback').length
   const queueActiveDisplay = analytics ? analytics.running_count : Math.min(queueActive, 3)
   const totalJobsCount = analytics ? analytics.total_jobs : jobs.length
   const runningJobs = jobs.filter((job) => job.status === 'running')
@@ -600,10 +610,10 @@ const JellyMoverShell = () => {
                   COLD
                 </div>
                 <div className="pool-title">
-                  {pools?.cold?.path || 'Archive / HDD Pool'}
-                  {pools?.cold && (
+                  {coldPool?.path || 'Archive / HDD Pool'}
+                  {coldPool && (
                     <span style={{ marginLeft: '0.5rem', opacity: 0.7, fontSize: '0.9em' }}>
-                      • {formatBytesShort(pools.cold.free_bytes)} free / {formatBytesShort(pools.cold.total_bytes)} ({coldUsagePercent}% used)
+                      • {formatBytesShort(coldPool.free_bytes)} free / {formatBytesShort(coldPool.total_bytes)} ({coldUsagePercent}% used)
                     </span>
                   )}
                 </div>
@@ -679,10 +689,10 @@ const JellyMoverShell = () => {
                   HOT
                 </div>
                 <div className="pool-title">
-                  {pools?.hot?.path || 'Now Playing / SSD Pool'}
-                  {pools?.hot && (
+                  {hotPool?.path || 'Now Playing / SSD Pool'}
+                  {hotPool && (
                     <span style={{ marginLeft: '0.5rem', opacity: 0.7, fontSize: '0.9em' }}>
-                      • {formatBytesShort(pools.hot.free_bytes)} free / {formatBytesShort(pools.hot.total_bytes)} ({hotUsagePercent}% used)
+                      • {formatBytesShort(hotPool.free_bytes)} free / {formatBytesShort(hotPool.total_bytes)} ({hotUsagePercent}% used)
                     </span>
                   )}
                 </div>
@@ -123,8 +123,9 @@ export function PoolUsageCharts({ pools, isLoading }: PoolUsageChartsProps) {
 
   return (
     <div className="pool-usage-charts">
-      <PoolChart title="Hot Pool" pool={pools?.hot || null} />
-      <PoolChart title="Cold Pool" pool={pools?.cold || null} />
+      {(pools?.tiers ?? []).map((tier) => (
+        <PoolChart key={tier.name} title={`${tier.name} (${tier.class})`} pool={tier.usage} />
+      ))}
     </div>
   )
 }
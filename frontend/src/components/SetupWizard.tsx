@@ -26,8 +26,8 @@ const SetupWizard = ({ initialConfig, onComplete }: SetupWizardProps) => {
   const [pathsError, setPathsError] = useState<string | null>(null)
   const [pathRootInput, setPathRootInput] = useState(DEFAULT_PATH_ROOT)
   const [pathsRoot, setPathsRoot] = useState(DEFAULT_PATH_ROOT)
-  const [hotRoot, setHotRoot] = useState(initialConfig?.hot_root ?? '')
-  const [coldRoot, setColdRoot] = useState(initialConfig?.cold_root ?? '')
+  const [hotRoot, setHotRoot] = useState(initialConfig?.tiers?.[0]?.roots[0] ?? '')
+  const [coldRoot, setColdRoot] = useState(initialConfig?.tiers?.[1]?.roots[0] ?? '')
   const [jellyfinUrl, setJellyfinUrl] = useState(initialConfig?.jellyfin.url ?? '')
   const [jellyfinApiKey, setJellyfinApiKey] = useState(initialConfig?.jellyfin.api_key ?? '')
   const [isSaving, setIsSaving] = useState(false)
@@ -108,8 +108,22 @@ const SetupWizard = ({ initialConfig, onComplete }: SetupWizardProps) => {
     const trimmedJellyfinApiKey = jellyfinApiKey.trim()
 
     const payload: AppConfig = {
-      hot_root: trimmedHot,
-      cold_root: trimmedCold,
+      tiers: [
+        {
+          name: 'hot',
+          roots: [trimmedHot],
+          class: 'ssd',
+          limits: { max_used_percent: null, min_free_bytes: null },
+          placement: 'most_free',
+        },
+        {
+          name: 'cold',
+          roots: [trimmedCold],
+          class: 'hdd',
+          limits: { max_used_percent: null, min_free_bytes: null },
+          placement: 'most_free',
+        },
+      ],
       library_paths: [],
       jellyfin: {
         url: trimmedJellyfinUrl,
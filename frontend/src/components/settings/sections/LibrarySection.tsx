@@ -1,7 +1,7 @@
 import React, { useState, useCallback, useEffect } from 'react';
 import { SettingsSection } from '../SettingsSection';
 import { apiGet, apiPost, apiPut } from '../../../api';
-import type { AppConfig, JellyfinStatus, ScanStatus } from '../../../types';
+import type { AppConfig, JellyfinStatus, ScanStatus, TierConfig } from '../../../types';
 import {
   SettingsCard,
   SettingsField,
@@ -34,6 +34,28 @@ const defaultFormState: SettingsFormState = {
   jellyfin_api_key: '',
 };
 
+const NO_LIMITS = { max_used_percent: null, min_free_bytes: null };
+
+/**
+ * Sets the first root of the fastest and slowest tiers, creating `hot`/`cold`
+ * tiers when fewer than two are configured. Extra roots and tiers in between
+ * are kept as-is.
+ */
+const withEdgeRoots = (tiers: TierConfig[], fastestRoot: string, slowestRoot: string): TierConfig[] => {
+  if (tiers.length < 2) {
+    return [
+      { name: 'hot', roots: [fastestRoot], class: 'ssd', limits: NO_LIMITS, placement: 'most_free' },
+      { name: 'cold', roots: [slowestRoot], class: 'hdd', limits: NO_LIMITS, placement: 'most_free' },
+    ];
+  }
+  const last = tiers.length - 1;
+  return tiers.map((tier, index) => {
+    if (index === 0) return { ...tier, roots: [fastestRoot, ...tier.roots.slice(1)] };
+    if (index === last) return { ...tier, roots: [slowestRoot, ...tier.roots.slice(1)] };
+    return tier;
+  });
+};
+
 const formatScanTimestamp = (timestamp?: number | null) => {
   if (!timestamp) {
     return 'Never';
@@ -53,6 +75,8 @@ export const LibrarySection: React.FC<LibrarySectionProps> = ({ onSave }) => {
   const [loading, setLoading] = useState(true);
   const [loadError, setLoadError] = useState<string | null>(null);
   const [formState, setFormState] = useState<SettingsFormState>(defaultFormState);
+  // Last loaded config, so fields this form does not edit survive a save
+  const [loadedConfig, setLoadedConfig] = useState<AppConfig | null>(null);
 
   const [saving, setSaving] = useState(false);
   const [saveMessage, setSaveMessage] = useState<string | null>(null);
@@ -79,9 +103,10 @@ export const LibrarySection: React.FC<LibrarySectionProps> = ({ onSave }) => {
     setLoadError(null);
     try {
       const config = await apiGet<AppConfig>('/config');
+      setLoadedConfig(config);
       setFormState({
-        hot_root: config.hot_root,
-        cold_root: config.cold_root,
+        hot_root: config.tiers[0]?.roots[0] ?? '',
+        cold_root: config.tiers.length > 1 ? config.tiers[config.tiers.length - 1].roots[0] ?? '' : '',
         libraryPathsText: config.library_paths.join('\n'),
         jellyfin_url: config.jellyfin.url,
         jellyfin_api_key: config.jellyfin.api_key,
@@ -178,8 +203,8 @@ export const LibrarySection: React.FC<LibrarySectionProps> = ({ onSave }) => {
       .filter(Boolean);
 
     const payload: AppConfig = {
-      hot_root: hotRoot,
-      cold_root: coldRoot,
+      ...loadedConfig,
+      tiers: withEdgeRoots(loadedConfig?.tiers ?? [], hotRoot, coldRoot),
       library_paths: libraryPaths,
       jellyfin: {
         url: jellyfinUrl,
@@ -189,6 +214,7 @@ export const LibrarySection: React.FC<LibrarySectionProps> = ({ onSave }) => {
 
     try {
       await apiPut('/config', payload);
+      setLoadedConfig(payload);
       setSaveMessage('Settings saved.');
       onSave?.();
     } catch (err) {
@@ -200,13 +226,13 @@ export const LibrarySection: React.FC<LibrarySectionProps> = ({ onSave }) => {
     }
   };
 
-  const triggerScan = async () => {
+  const triggerScan = async (full = false) => {
     setScanError(null);
     setScanMessage(null);
     setScanLoading(true);
     try {
-      await apiPost('/scan');
-      setScanMessage('Library rescan kicked off.');
+      await apiPost(full ? '/scan?full=true' : '/scan');
+      setScanMessage(full ? 'Full library rescan kicked off.' : 'Library rescan kicked off.');
       await loadScanStatus();
     } catch (err) {
       console.error('Failed to start scan', err);
@@ -217,6 +243,20 @@ export const LibrarySection: React.FC<LibrarySectionProps> = ({ onSave }) => {
     }
   };
 
+  const cancelScan = async () => {
+    setScanError(null);
+    setScanMessage(null);
+    try {
+      await apiPost('/scan/cancel');
+      setScanMessage('Scan will stop after the current show.');
+      await loadScanStatus();
+    } catch (err) {
+      console.error('Failed to cancel scan', err);
+      const message = err instanceof Error ? err.message : 'Unknown error';
+      setScanError(`Failed to cancel scan (${message}).`);
+    }
+  };
+
   const triggerJellyfinScan = async () => {
     setJellyfinScanError(null);
     setJellyfinScanMessage(null);
@@ -289,16 +329,16 @@ export const LibrarySection: React.FC<LibrarySectionProps> = ({ onSave }) => {
         <SettingsCard title="Paths & Pools" description="Configure where JellyMover can find your HOT and COLD pools.">
           <SettingsPathPicker
             id="hot-root"
-            label="Hot root path"
-            hint="Path to your fast storage pool (SSD/NVMe)"
+            label="Fastest tier root path"
+            hint="Path to your fastest storage tier (SSD/NVMe)"
             value={formState.hot_root}
             onChange={(value) => handleInputChange('hot_root', value)}
           />
 
           <SettingsPathPicker
             id="cold-root"
-            label="Cold root path"
-            hint="Path to your large capacity storage pool (HDD)"
+            label="Slowest tier root path"
+            hint="Path to your largest capacity storage tier (HDD). Tiers in between are set in the config file."
             value={formState.cold_root}
             onChange={(value) => handleInputChange('cold_root', value)}
           />
@@ -371,12 +411,31 @@ export const LibrarySection: React.FC<LibrarySectionProps> = ({ onSave }) => {
             <SettingsButton
               type="button"
               variant="primary"
-              onClick={triggerScan}
+              onClick={() => triggerScan()}
               loading={scanLoading}
               loadingText="Rescanning..."
             >
               Rescan Library
             </SettingsButton>
+            <SettingsButton
+              type="button"
+              variant="ghost"
+              onClick={() => triggerScan(true)}
+              disabled={scanLoading}
+            >
+              Full Rescan
+            </SettingsButton>
+            {scanStatus?.state === 'Running' && (
+              <SettingsButton
+                type="button"
+                variant="ghost"
+                onClick={cancelScan}
+                disabled={scanStatus.cancel_requested}
+              >
+                {scanStatus.cancel_requested ? 'Cancelling...' : 'Cancel Scan'}
+              </SettingsButton>
+            )}
+            <SettingsHint>Rescans skip shows whose folders have not changed; a full rescan reads every show again.</SettingsHint>
             <div className="settings-info-stack">
               <SettingsInfoRow
                 label="Scan status"
@@ -387,6 +446,12 @@ export const LibrarySection: React.FC<LibrarySectionProps> = ({ onSave }) => {
                   </span>
                 ) : 'Loading…'}
               />
+              {scanStatus?.progress && (
+                <SettingsInfoRow
+                  label="Progress"
+                  value={`${scanStatus.progress.shows_done} / ${scanStatus.progress.shows_total} shows, ${scanStatus.progress.elapsed_seconds}s${scanStatus.progress.current_path ? ` (${scanStatus.progress.current_path})` : ''}`}
+                />
+              )}
               <SettingsInfoRow
                 label="Last started"
                 value={formatScanTimestamp(scanStatus?.last_started)}
@@ -1,6 +1,6 @@
 import { useCallback, useEffect, useRef, useState } from 'react'
 import { apiGet } from '../api'
-import type { Job } from '../types'
+import type { Job, Page } from '../types'
 
 interface UseJobsPollingOptions {
   intervalMs?: number
@@ -63,12 +63,14 @@ const useJobsPolling = (options: UseJobsPollingOptions = {}): UseJobsPollingResu
         }
         const query = params.toString()
         const url = query ? `/jobs?${query}` : '/jobs'
-        const data = await apiGet<Job[]>(url)
+        const { items: data } = await apiGet<Page<Job>>(url)
         if (!cancelRef.current) {
           setJobs(data)
           setLastUpdated(new Date())
           setError(null)
-          const hasActive = data.some((job) => job.status === 'queued' || job.status === 'running')
+          const hasActive = data.some(
+            (job) => job.status === 'queued' || job.status === 'running' || job.status === 'waiting_for_playback',
+          )
           delayRef.current = hasActive ? busyInterval : idleInterval
         }
       } catch (err) {
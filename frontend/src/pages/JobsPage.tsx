@@ -2,7 +2,7 @@ import { useEffect, useMemo, useState } from 'react'
 import AppHeader from '../components/AppHeader'
 import { apiGet } from '../api'
 import useJobsPolling from '../hooks/useJobsPolling'
-import type { Job } from '../types'
+import type { Job, Page } from '../types'
 import {
   formatBytesShort,
   formatEta,
@@ -82,10 +82,11 @@ const JobsPage = () => {
         limit: JOBS_PAGE_SIZE.toString(),
         offset: jobsOffset.toString(),
       })
-      const data = await apiGet<Job[]>(`/jobs?${params.toString()}`)
+      const page = await apiGet<Page<Job>>(`/jobs?${params.toString()}`)
+      const data = page.items
       setHistoricalJobs((prev) => [...prev, ...data])
       setJobsOffset((prev) => prev + data.length)
-      if (data.length < JOBS_PAGE_SIZE) {
+      if (jobsOffset + data.length >= page.total) {
         setJobsHasMore(false)
       }
     } catch (err) {
@@ -7,7 +7,7 @@ import { TransferSpeedChart } from '../components/TransferSpeedChart'
 import { apiGet } from '../api'
 import useJobsPolling from '../hooks/useJobsPolling'
 import { useSystemMetricsHistory, useJobAnalytics } from '../hooks/useMetrics'
-import type { PoolsResponse, Show, DurationOption } from '../types'
+import type { Page, PoolsResponse, Show, DurationOption } from '../types'
 import {
   formatJobDirection,
   formatShowSize,
@@ -19,8 +19,7 @@ const StatsPage = () => {
   const [isLoadingPools, setIsLoadingPools] = useState(false)
   const [poolsError, setPoolsError] = useState<string | null>(null)
   const { jobs, isLoading: isLoadingJobs, error: jobsError } = useJobsPolling({ intervalMs: 2000 })
-  const [coldMedia, setColdMedia] = useState<Show[]>([])
-  const [hotMedia, setHotMedia] = useState<Show[]>([])
+  const [media, setMedia] = useState<Show[]>([])
   const [duration, setDuration] = useState<DurationOption>('1h')
 
   // Fetch historical metrics
@@ -64,13 +63,9 @@ const StatsPage = () => {
 
     const loadMedia = async () => {
       try {
-        const [coldData, hotData] = await Promise.all([
-          apiGet<Show[]>('/shows?location=cold&limit=1000'),
-          apiGet<Show[]>('/shows?location=hot&limit=1000'),
-        ])
+        const data = await apiGet<Page<Show>>('/shows?limit=1000')
         if (cancelled) return
-        setColdMedia(coldData)
-        setHotMedia(hotData)
+        setMedia(data.items)
       } catch (err) {
         console.error('Failed to load media for title mapping', err)
       }
@@ -85,16 +80,12 @@ const StatsPage = () => {
 
   const showTitleMap = useMemo(() => {
     const map = new Map<number, string>()
-    coldMedia.forEach((show) => {
-      const safeTitle = show.title?.trim() || 'Untitled show'
-      map.set(show.id, safeTitle)
-    })
-    hotMedia.forEach((show) => {
+    media.forEach((show) => {
       const safeTitle = show.title?.trim() || 'Untitled show'
       map.set(show.id, safeTitle)
     })
     return map
-  }, [coldMedia, hotMedia])
+  }, [media])
 
   const runningJobs = jobs.filter((job) => job.status === 'running')
   const etaValues = runningJobs
@@ -1,13 +1,146 @@
+export type StorageClass = 'nvme' | 'ssd' | 'hdd' | 'archive'
+
+export interface TierLimits {
+  max_used_percent: number | null
+  min_free_bytes: number | null
+}
+
+/** How a move picks one of a tier's roots. */
+export type PlacementStrategy = 'most_free' | 'round_robin' | 'sticky_per_library'
+
+export interface TierConfig {
+  name: string
+  roots: string[]
+  class: StorageClass
+  limits: TierLimits
+  placement: PlacementStrategy
+  /** Show folders scanned at once; defaults by `class`. */
+  scan_parallelism?: number | null
+}
+
+export type MediaKind = 'series' | 'movie' | 'other'
+
+export interface LibraryKind {
+  library: string
+  kind: MediaKind
+}
+
+export interface LibraryQuota {
+  library: string
+  tier: string | null
+  max_bytes: number | null
+  max_percent: number | null
+}
+
 export interface JellyfinSettings {
   url: string
   api_key: string
 }
 
 export interface AppConfig {
-  hot_root: string
-  cold_root: string
+  /** Storage tiers, fastest first. */
+  tiers: TierConfig[]
   library_paths: string[]
+  library_quotas?: LibraryQuota[]
+  library_kinds?: LibraryKind[]
   jellyfin: JellyfinSettings
+  job_retention?: JobRetentionSettings
+  policies?: PolicySettings
+  eviction?: EvictionSettings
+  activity?: ActivitySettings
+  prefetch?: PrefetchSettings
+  stable_paths?: StablePathsSettings
+  mergerfs?: MergerfsSettings
+  watcher?: WatcherSettings
+  scan?: ScanSettings
+}
+
+export interface WatcherSettings {
+  enabled: boolean
+  debounce_seconds: number
+}
+
+export interface ScanSettings {
+  /** Show folders scanned at once in libraries outside every tier. */
+  parallelism: number
+  batch_size: number
+}
+
+/** A mergerfs union whose branches hold the tier roots. */
+export interface MergerfsSettings {
+  enabled: boolean
+  union_root: string
+  /** Empty reads the branch list from the mounted union. */
+  branches: string[]
+}
+
+export interface MergerfsStatus {
+  enabled: boolean
+  union_root: string
+  source: 'config' | 'mount'
+  branches: { path: string; tiers: string[] }[]
+}
+
+/** Per-show symlinks under `farm_root` that Jellyfin reads instead of the tier folders. */
+export interface StablePathsSettings {
+  enabled: boolean
+  farm_root: string
+}
+
+export interface FarmConflict {
+  link: string
+  reason: string
+}
+
+export interface FarmReport {
+  dry_run: boolean
+  links: number
+  created: string[]
+  retargeted: string[]
+  removed: string[]
+  conflicts: FarmConflict[]
+}
+
+export interface PrefetchSettings {
+  enabled: boolean
+  interval_minutes: number
+  episodes_ahead: number
+  hot_budget_bytes: number
+}
+
+export interface ActivitySettings {
+  sync_interval_minutes: number
+  active_viewer_days: number
+}
+
+export interface EvictionSettings {
+  enabled: boolean
+  interval_minutes: number
+  high_watermark_percent: number
+  low_watermark_percent: number
+  idle_weight: number
+  size_weight: number
+}
+
+export interface PolicySettings {
+  enabled: boolean
+  interval_minutes: number
+  max_jobs_per_run: number
+  rules: PolicyRule[]
+}
+
+export interface PolicyRule {
+  name: string
+  enabled: boolean
+  /** Tier name. */
+  target: string
+  conditions: Array<{ kind: string; [key: string]: unknown }>
+}
+
+export interface JobRetentionSettings {
+  success_days: number | null
+  failed_days: number | null
+  archive_dir: string
 }
 
 export interface UserSettings {
@@ -25,12 +158,70 @@ export interface PathEntry {
 export interface Show {
   id: number
   title: string | null
+  kind: MediaKind
   path: string
   location: string | null
   size_bytes: number | null
   season_count: number | null
   episode_count: number | null
   thumbnail_path?: string | null
+  pinned_location?: string | null
+  last_played_at?: number | null
+  play_count?: number
+  active_viewers?: number
+  /** When the show's folder was found gone (unix seconds). */
+  missing_since?: number | null
+  /** Metadata from the show's tvshow.nfo. */
+  original_title?: string | null
+  year?: number | null
+  premiered?: string | null
+  end_date?: string | null
+  rating?: number | null
+  series_status?: string | null
+  genres?: string[]
+  tags?: string[]
+  studios?: string[]
+  /** Provider name to id, e.g. `tvdb`. */
+  provider_ids?: Record<string, string>
+  /** Path inside the mergerfs union, when mergerfs mode is on. */
+  union_path?: string
+}
+
+export interface Episode {
+  id: number
+  season: number
+  episode: number
+  title: string | null
+  aired: string | null
+  runtime_minutes: number | null
+  /** Video path relative to the show folder. */
+  relative_path: string
+  /** Video plus subtitles, NFO and thumbnails. */
+  size_bytes: number
+  video_codec: string | null
+  audio_codec: string | null
+  width: number | null
+  height: number | null
+  bitrate: number | null
+}
+
+export interface SeasonSummary {
+  season: number
+  episode_count: number
+  size_bytes: number
+}
+
+export interface ShowEpisodes {
+  show_id: number
+  seasons: SeasonSummary[]
+  episodes: Episode[]
+}
+
+export interface Page<T> {
+  items: T[]
+  total: number
+  limit: number
+  offset: number
 }
 
 export interface PoolInfo {
@@ -40,9 +231,27 @@ export interface PoolInfo {
   free_bytes: number
 }
 
+export interface TierSummary {
+  name: string
+  class: StorageClass
+  /** Aggregated across the tier's roots. */
+  usage: PoolInfo | null
+  roots: PoolInfo[]
+}
+
 export interface PoolsResponse {
-  hot: PoolInfo | null
-  cold: PoolInfo | null
+  /** Every configured tier, fastest first. */
+  tiers: TierSummary[]
+  quotas: QuotaUsage[]
+}
+
+export interface QuotaUsage {
+  library: string
+  tier: string
+  max_bytes: number | null
+  max_percent: number | null
+  limit_bytes: number | null
+  used_bytes: number
 }
 
 export interface Job {
@@ -58,6 +267,15 @@ export interface Job {
   error_message: string | null
   created_at: number | null
   updated_at: number | null
+  started_at: number | null
+  finished_at: number | null
+  duration_seconds: number | null
+  bytes_copied_this_run: number | null
+  attempt: number
+  strategy: string | null
+  kind: 'move' | 'prefetch' | 'prefetch_return'
+  source_tier: string | null
+  target_tier: string | null
 }
 
 export interface ScanStatus {
@@ -65,6 +283,36 @@ export interface ScanStatus {
   last_started: number | null
   last_finished: number | null
   last_error: string | null
+  /** Live progress of the running scan. */
+  progress?: ScanProgress | null
+  cancel_requested?: boolean
+}
+
+export interface ScanProgress {
+  library: string | null
+  shows_done: number
+  /** Show folders found so far; grows while libraries are being listed. */
+  shows_total: number
+  current_path: string | null
+  elapsed_seconds: number
+}
+
+/** A finished filesystem scan from `GET /api/scan/runs`. */
+export interface ScanRun {
+  id: number
+  started_at: number
+  finished_at: number
+  full: boolean
+  status: 'success' | 'failed' | 'cancelled'
+  scanned_libraries: number
+  shows_processed: number
+  inserted: number
+  updated: number
+  unchanged: number
+  removed: number
+  purged: number
+  error: string | null
+  warnings: string[]
 }
 
 export interface JellyfinStatus {
@@ -94,7 +342,7 @@ export interface SystemMetricsHistoryPoint {
 
 export interface PoolUsageHistoryPoint {
   timestamp: number
-  pool_type: string
+  tier: string
   total_bytes: number
   used_bytes: number
   free_bytes: number
@@ -104,9 +352,25 @@ export interface JobAnalytics {
   total_jobs: number
   running_count: number
   queued_count: number
+  waiting_for_playback_count?: number
   completed_count: number
   failed_count: number
   total_bytes_moved: number
+  bucket_seconds: number
+  buckets: JobAnalyticsBucket[]
+}
+
+export interface JobAnalyticsBucket {
+  bucket_start: number
+  completed_count: number
+  failed_count: number
+  failure_rate: number
+  bytes_promoted: number
+  bytes_demoted: number
+  mean_duration_secs: number | null
+  p50_duration_secs: number | null
+  p95_duration_secs: number | null
+  mean_throughput_bytes_per_sec: number | null
 }
 
 export type DurationOption = '1h' | '6h' | '24h' | '7d' | '30d'
@@ -25,13 +25,10 @@ export const getJobProgressPercent = (job: Job) => {
 }
 
 export const formatJobDirection = (job: Job) => {
-  const src = job.source_path.toLowerCase()
-  const dest = job.destination_path.toLowerCase()
-  if (src === dest) return 'MOVE'
-  if (src.includes('hot') && dest.includes('cold')) return 'HOT → COLD'
-  if (src.includes('cold') && dest.includes('hot')) return 'COLD → HOT'
-  if (dest.includes('hot')) return 'TO HOT'
-  if (dest.includes('cold')) return 'TO COLD'
+  if (job.source_tier && job.target_tier && job.source_tier !== job.target_tier) {
+    return `${job.source_tier.toUpperCase()} → ${job.target_tier.toUpperCase()}`
+  }
+  if (job.target_tier) return `TO ${job.target_tier.toUpperCase()}`
   return 'MOVE'
 }
 